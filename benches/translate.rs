@@ -0,0 +1,140 @@
+//! Measures the cost of translating button-only, mouse-only and scroll workloads. The scroll
+//! lookup is also measured against a baseline which allocates on every call, as the
+//! translator used to. This uses a plain `main` so that it runs on the stable compiler:
+//! `cargo bench --bench translate`.
+
+extern crate input;
+extern crate rebind;
+
+use input::{Input, Motion};
+use input::Button::Keyboard;
+use input::keyboard::Key;
+use rebind::{Action, Builder, ScrollDir};
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum BenchAction {
+    Jump,
+    Crouch,
+    Fire
+}
+
+impl Action for BenchAction { }
+
+const ITERATIONS: u32 = 1_000_000;
+
+fn run<F: FnMut(u32)>(name: &str, mut f: F) {
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        f(i);
+    }
+    let elapsed = start.elapsed();
+    let nanos = elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64;
+    println!("{}: {:?} total, {} ns/event", name, elapsed, nanos / ITERATIONS as u64);
+}
+
+fn mapped_action(keymap: &[(ScrollDir, BenchAction, bool)], dir: ScrollDir) -> Option<BenchAction> {
+    keymap.iter()
+          .find(|&&(mapped, _, invert)| if invert { mapped.opposite() == dir } else { mapped == dir })
+          .map(|&(_, a, _)| a)
+}
+
+fn axis_dir(value: f64, positive: ScrollDir, negative: ScrollDir) -> Option<ScrollDir> {
+    if value > 0.0 {
+        Some(positive)
+    } else if value < 0.0 {
+        Some(negative)
+    } else {
+        None
+    }
+}
+
+/// The scroll lookup which the translator used before it stopped allocating: the
+/// directions of the scroll are collected into a `Vec`, then searched in turn.
+#[inline(never)]
+fn baseline_scroll_action(keymap: &[(ScrollDir, BenchAction, bool)], x: f64, y: f64) -> Option<BenchAction> {
+    let dirs = axis_dir(y, ScrollDir::Up, ScrollDir::Down)
+                   .into_iter()
+                   .chain(axis_dir(x, ScrollDir::Right, ScrollDir::Left))
+                   .collect::<Vec<_>>();
+    dirs.into_iter().filter_map(|dir| mapped_action(keymap, dir)).next()
+}
+
+/// The scroll lookup which the translator uses now, which checks each axis in turn.
+#[inline(never)]
+fn scroll_action(keymap: &[(ScrollDir, BenchAction, bool)], x: f64, y: f64) -> Option<BenchAction> {
+    if keymap.is_empty() {
+        return None;
+    }
+    axis_dir(y, ScrollDir::Up, ScrollDir::Down)
+        .and_then(|dir| mapped_action(keymap, dir))
+        .or_else(|| axis_dir(x, ScrollDir::Right, ScrollDir::Left).and_then(|dir| mapped_action(keymap, dir)))
+}
+
+fn main() {
+    let mut translator = Builder::<BenchAction>::new((800, 600))
+                             .with_mapping(BenchAction::Jump, Keyboard(Key::Space))
+                             .with_mapping(BenchAction::Crouch, Keyboard(Key::LCtrl))
+                             .with_mapping(BenchAction::Fire, Keyboard(Key::F))
+                             .x_motion_inverted(true)
+                             .build_translator();
+
+    let buttons = [Input::Press(Keyboard(Key::Space)),
+                   Input::Release(Keyboard(Key::Space)),
+                   Input::Press(Keyboard(Key::F)),
+                   Input::Release(Keyboard(Key::F)),
+                   Input::Press(Keyboard(Key::Q)),
+                   Input::Release(Keyboard(Key::Q))];
+    let mut translated = 0;
+    run("buttons", |i| {
+        if translator.translate(&buttons[i as usize % buttons.len()]).is_some() {
+            translated += 1;
+        }
+    });
+
+    let mut translated_motion = 0;
+    run("mouse cursor", |i| {
+        let motion = Input::Move(Motion::MouseCursor((i % 800) as f64, (i % 600) as f64));
+        if translator.translate(&motion).is_some() {
+            translated_motion += 1;
+        }
+    });
+
+    let scroll_keymap = [(ScrollDir::Up, BenchAction::Jump, false), (ScrollDir::Right, BenchAction::Fire, true)];
+    let mut scroll_translator = Builder::<BenchAction>::new((800, 600))
+                                    .with_scroll_mapping(ScrollDir::Up, BenchAction::Jump, false)
+                                    .with_scroll_mapping(ScrollDir::Right, BenchAction::Fire, true)
+                                    .build_translator();
+    let scrolls = [(0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)];
+    let scroll = |i: u32| scrolls[i as usize % scrolls.len()];
+
+    let mut translated_scroll = 0;
+    run("scroll", |i| {
+        let (x, y) = scroll(i);
+        if scroll_translator.translate(&Input::Move(Motion::MouseScroll(x, y))).is_some() {
+            translated_scroll += 1;
+        }
+    });
+
+    let mut baseline_found = 0;
+    run("scroll lookup, baseline", |i| {
+        let (x, y) = scroll(i);
+        if baseline_scroll_action(&scroll_keymap, x, y).is_some() {
+            baseline_found += 1;
+        }
+    });
+
+    let mut found = 0;
+    run("scroll lookup", |i| {
+        let (x, y) = scroll(i);
+        if scroll_action(&scroll_keymap, x, y).is_some() {
+            found += 1;
+        }
+    });
+    assert_eq!(found, baseline_found);
+
+    println!("translated {} button events, {} motion events and {} scroll events",
+             translated,
+             translated_motion,
+             translated_scroll);
+}
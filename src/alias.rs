@@ -0,0 +1,44 @@
+use Action;
+use std::collections::HashMap;
+
+/// A table of alternative names for actions, used when reading bindings from text so that
+/// e.g. `"Jump"` and `"Leap"` both refer to the same action. This is useful for localized
+/// configs, or configs saved before an action was renamed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AliasTable<A: Action> {
+    aliases: HashMap<String, A>
+}
+
+impl<A: Action> AliasTable<A> {
+    /// Creates a table with no aliases.
+    pub fn new() -> Self {
+        AliasTable { aliases: HashMap::new() }
+    }
+
+    /// Add a name which refers to an action, replacing any action which the name already
+    /// referred to.
+    pub fn add_alias(&mut self, name: &str, action: A) {
+        self.aliases.insert(name.to_owned(), action);
+    }
+
+    /// Returns the action which a name refers to.
+    pub fn resolve(&self, name: &str) -> Option<A> {
+        self.aliases.get(name).cloned()
+    }
+
+    /// Returns the number of aliases in the table.
+    pub fn len(&self) -> usize {
+        self.aliases.len()
+    }
+
+    /// Returns true if the table contains no aliases.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+}
+
+impl<A: Action> Default for AliasTable<A> {
+    fn default() -> Self {
+        AliasTable::new()
+    }
+}
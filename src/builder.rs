@@ -1,16 +1,23 @@
-use {Action, InputRebind, InputTranslator, MouseTranslationData, to_act_bt_hashmap};
+use {Action, ButtonTuple, InputRebind, InputTranslator, ModifiedButton, ModifierMask, MouseTranslationData,
+     ScrollDir, ViewportSize, tap_holds_from_parts, tap_holds_to_parts, to_act_bt_hashmap};
 use input::Button;
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
 use window::Size;
 use std::convert::Into;
 use std::collections::hash_map::RandomState;
 use std::hash::BuildHasher;
 use std::default::Default;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 /// Convenience object for constructing an InputMap.
 #[derive(Debug)]
 pub struct Builder<A: Action, S: BuildHasher = RandomState> {
     input_remappings: Vec<(Button, A)>,
+    modified_remappings: Vec<(ModifiedButton, A)>,
+    tap_hold_remappings: Vec<(Button, A, A, Duration)>,
+    scroll_remappings: Vec<(ScrollDir, A, bool)>,
+    registered_actions: Vec<A>,
     mouse_data: MouseTranslationData,
     _hasher: PhantomData<S>
 }
@@ -20,6 +27,10 @@ impl<A: Action, S: BuildHasher + Default> Builder<A, S> {
     pub fn new<Sz: Into<Size>>(size: Sz) -> Self {
         Builder {
             input_remappings: vec![],
+            modified_remappings: vec![],
+            tap_hold_remappings: vec![],
+            scroll_remappings: vec![],
+            registered_actions: vec![],
             mouse_data: MouseTranslationData::new(size),
             _hasher: PhantomData
         }
@@ -47,7 +58,8 @@ impl<A: Action, S: BuildHasher + Default> Builder<A, S> {
         self.mouse_data.y_axis_scroll_inverted
     }
 
-    /// Set whether the x axis motion is inverted on the builder.
+    /// Set whether the x axis motion is inverted on the builder. An inverted cursor
+    /// position `x` is translated to `width - x`.
     pub fn x_motion_inverted(mut self, invert: bool) -> Self {
         self.mouse_data.x_axis_motion_inverted = invert;
         self
@@ -58,7 +70,8 @@ impl<A: Action, S: BuildHasher + Default> Builder<A, S> {
         self.mouse_data.x_axis_motion_inverted
     }
 
-    /// Set whether the y axis motion is inverted on the builder.
+    /// Set whether the y axis motion is inverted on the builder. An inverted cursor
+    /// position `y` is translated to `height - y`.
     pub fn y_motion_inverted(mut self, invert: bool) -> Self {
         self.mouse_data.y_axis_motion_inverted = invert;
         self
@@ -69,7 +82,8 @@ impl<A: Action, S: BuildHasher + Default> Builder<A, S> {
         self.mouse_data.y_axis_motion_inverted
     }
 
-    /// Set the mouse sensitivity.
+    /// Set the mouse sensitivity, which scales every relative mouse motion before it is
+    /// clamped to the maximum delta. The default is 1.
     pub fn mouse_sensitivity(mut self, sensitivity: f64) -> Self {
         self.mouse_data.sensitivity = sensitivity;
         self
@@ -80,9 +94,89 @@ impl<A: Action, S: BuildHasher + Default> Builder<A, S> {
         self.mouse_data.sensitivity
     }
 
+    /// Set the maximum magnitude of a single relative mouse motion, after scaling by the
+    /// sensitivity. Larger motions are scaled down to this length, keeping their direction.
+    /// `None` disables clamping.
+    pub fn max_mouse_delta(mut self, max_delta: Option<f64>) -> Self {
+        self.mouse_data.max_delta = max_delta;
+        self
+    }
+
+    /// Returns the maximum magnitude of a single relative mouse motion.
+    pub fn get_max_mouse_delta(&self) -> Option<f64> {
+        self.mouse_data.max_delta
+    }
+
+    /// Set whether mouse scrolling is translated into relative mouse motion instead of
+    /// scrolling, e.g. for an accessibility mode where the scroll wheel moves the cursor.
+    /// The scroll is inverted as usual before being scaled by the scroll motion factor.
+    pub fn scroll_as_motion(mut self, enabled: bool) -> Self {
+        self.mouse_data.scroll_as_motion = enabled;
+        self
+    }
+
+    /// Returns true if mouse scrolling is translated into relative mouse motion.
+    pub fn get_scroll_as_motion(&self) -> bool {
+        self.mouse_data.scroll_as_motion
+    }
+
+    /// Set the factor by which a scroll is multiplied when it is translated into relative
+    /// mouse motion. The default factor is `1.0`.
+    pub fn scroll_motion_factor(mut self, factor: f64) -> Self {
+        self.mouse_data.scroll_motion_factor = factor;
+        self
+    }
+
+    /// Returns the factor by which a scroll is multiplied when it is translated into
+    /// relative mouse motion.
+    pub fn get_scroll_motion_factor(&self) -> f64 {
+        self.mouse_data.scroll_motion_factor
+    }
+
+    /// Set the number of physical pixels per logical unit, e.g. `2.0` on a HiDPI display.
+    /// Translated cursor positions are divided by this scale, so that they are in logical
+    /// units when the cursor position and viewport size are in physical pixels.
+    pub fn dpi_scale(mut self, dpi_scale: f64) -> Self {
+        self.mouse_data.dpi_scale = dpi_scale;
+        self
+    }
+
+    /// Returns the number of physical pixels per logical unit.
+    pub fn get_dpi_scale(&self) -> f64 {
+        self.mouse_data.dpi_scale
+    }
+
+    /// Set whether translated cursor positions are normalized to the range `[0, 1]` on each
+    /// axis, by dividing them by the viewport size. Inversion is applied first, and the DPI
+    /// scale is not applied to normalized positions.
+    pub fn normalize_cursor(mut self, normalize: bool) -> Self {
+        self.mouse_data.normalize_cursor = normalize;
+        self
+    }
+
+    /// Returns true if translated cursor positions are normalized.
+    pub fn get_normalize_cursor(&self) -> bool {
+        self.mouse_data.normalize_cursor
+    }
+
+    /// Set whether cursor positions are translated into relative mouse motion from the
+    /// centre of the viewport, e.g. for a first person camera which warps the cursor back
+    /// to the centre every frame. Inversion is applied first, and the position is neither
+    /// normalized nor scaled.
+    pub fn lock_to_center(mut self, lock: bool) -> Self {
+        self.mouse_data.lock_to_center = lock;
+        self
+    }
+
+    /// Returns true if cursor positions are translated into motion relative to the centre
+    /// of the viewport.
+    pub fn get_lock_to_center(&self) -> bool {
+        self.mouse_data.lock_to_center
+    }
+
     /// Sets the viewport size used for mouse position calculations.
-    pub fn viewport_size(mut self, size: Size) -> Self {
-        self.mouse_data.viewport_size = size;
+    pub fn viewport_size<Sz: Into<ViewportSize>>(mut self, size: Sz) -> Self {
+        self.mouse_data.viewport_size = Size::from(size.into());
         self
     }
 
@@ -97,17 +191,98 @@ impl<A: Action, S: BuildHasher + Default> Builder<A, S> {
         self
     }
 
+    /// Add an association for each `(Button, Action)` pair yielded by `mappings`, in order,
+    /// as if by calling `with_mapping` for each of them.
+    pub fn with_mappings<I: IntoIterator<Item = (Button, A)>>(mut self, mappings: I) -> Self {
+        self.input_remappings.extend(mappings);
+        self
+    }
+
+    /// Add an association between the Button, pressed while exactly the modifier keys in
+    /// `mods` are held, and the Action. When a modified mapping matches a press, it takes
+    /// priority over a plain mapping of the same button. Modified mappings are only used
+    /// by an `InputTranslator`, and are not kept in an `InputRebind`.
+    pub fn with_modified_mapping(mut self, action: A, button: Button, mods: ModifierMask) -> Self {
+        self.modified_remappings.push((ModifiedButton { button, mods }, action));
+        self
+    }
+
+    /// Add a mapping of a Button to two actions, `tap_action` for when the button is
+    /// released within `threshold` of being pressed, and `hold_action` for when it is held
+    /// for longer. Tap/hold mappings are only used by `InputTranslator::translate_at`, and
+    /// are not kept in an `InputRebind`.
+    pub fn with_tap_hold_mapping(mut self, button: Button, tap_action: A, hold_action: A, threshold: Duration) -> Self {
+        self.tap_hold_remappings.push((button, tap_action, hold_action, threshold));
+        self
+    }
+
+    /// Add a mapping of a direction of mouse scrolling to an action, e.g. for cycling
+    /// weapons with the scroll wheel. Each scroll in that direction is translated into a
+    /// press of the action, instead of into mouse motion. If `invert` is true, the mapping
+    /// matches scrolls in the opposite direction instead. This is independent of the global
+    /// scroll inversion, which only affects scrolls translated into motion. Scroll mappings
+    /// are only used by an `InputTranslator`, and are not kept in an `InputRebind`.
+    pub fn with_scroll_mapping(mut self, dir: ScrollDir, action: A, invert: bool) -> Self {
+        self.scroll_remappings.push((dir, action, invert));
+        self
+    }
+
+    /// Register an action without binding any buttons to it, so that it appears in a
+    /// built `InputRebind` with an empty `ButtonTuple`. This is useful for showing every
+    /// action in a rebinding UI, including those which are unbound.
+    pub fn with_action(mut self, action: A) -> Self {
+        self.registered_actions.push(action);
+        self
+    }
+
     /// Creates an `InputTranslator` from this builder object.
     pub fn build_translator(self) -> InputTranslator<A, S> {
         self.into()
     }
 
-    /// Creates an `InputRebind` from this builder object.
+    /// Creates an `InputRebind` from this builder object. The buttons in each action's
+    /// `ButtonTuple` are stored in the order in which they were added to the builder, so
+    /// the first button mapped to an action will always be in slot 0.
     pub fn build_rebind(self) -> InputRebind<A, S> {
         self.into()
     }
 }
 
+/// The builder is serialized with its remappings in the order in which they were added,
+/// so a deserialized builder will build the same `InputRebind` and `InputTranslator` as
+/// the original. This isn't behind a feature, since `rustc-serialize` is a required
+/// dependency and the translator and rebind are always serializable too.
+impl<A: Action + Encodable, S: BuildHasher> Encodable for Builder<A, S> {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        e.emit_struct("Builder", 6, |e| {
+            e.emit_struct_field("input_remappings", 0, |e| self.input_remappings.encode(e))?;
+            e.emit_struct_field("modified_remappings", 1, |e| self.modified_remappings.encode(e))?;
+            e.emit_struct_field("registered_actions", 2, |e| self.registered_actions.encode(e))?;
+            e.emit_struct_field("mouse_data", 3, |e| self.mouse_data.encode(e))?;
+            e.emit_struct_field("tap_hold_remappings", 4, |e| tap_holds_to_parts(&self.tap_hold_remappings).encode(e))?;
+            e.emit_struct_field("scroll_remappings", 5, |e| self.scroll_remappings.encode(e))
+        })
+    }
+}
+
+impl<A: Action + Decodable, S: BuildHasher> Decodable for Builder<A, S> {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("Builder", 6, |d| {
+            Ok(Builder {
+                input_remappings: d.read_struct_field("input_remappings", 0, Decodable::decode)?,
+                modified_remappings: d.read_struct_field("modified_remappings", 1, Decodable::decode)?,
+                tap_hold_remappings: tap_holds_from_parts(d.read_struct_field("tap_hold_remappings",
+                                                                              4,
+                                                                              Decodable::decode)?),
+                scroll_remappings: d.read_struct_field("scroll_remappings", 5, Decodable::decode)?,
+                registered_actions: d.read_struct_field("registered_actions", 2, Decodable::decode)?,
+                mouse_data: d.read_struct_field("mouse_data", 3, Decodable::decode)?,
+                _hasher: PhantomData
+            })
+        })
+    }
+}
+
 impl<A: Action, S: BuildHasher + Default> Default for Builder<A, S> {
     fn default() -> Self {
         Self::new((800, 600))
@@ -115,23 +290,31 @@ impl<A: Action, S: BuildHasher + Default> Default for Builder<A, S> {
 }
 
 
-impl<A: Action, S: BuildHasher + Default> Into<InputTranslator<A, S>> for Builder<A, S> {
-    fn into(self) -> InputTranslator<A, S> {
-        let mut translator = InputTranslator::new(self.mouse_data.viewport_size);
+impl<A: Action, S: BuildHasher + Default> From<Builder<A, S>> for InputTranslator<A, S> {
+    fn from(builder: Builder<A, S>) -> InputTranslator<A, S> {
+        let mut translator = InputTranslator::new(builder.mouse_data.viewport_size);
 
-        translator.mouse_translator.data = self.mouse_data;
-        translator.keymap = self.input_remappings.iter().cloned().collect();
+        translator.mouse_translator.data = builder.mouse_data;
+        translator.keymap = builder.input_remappings.iter().cloned().collect();
+        translator.modified_keymap = builder.modified_remappings.iter().cloned().collect();
+        translator.tap_hold_keymap = builder.tap_hold_remappings;
+        translator.scroll_keymap = builder.scroll_remappings;
 
         translator
     }
 }
 
-impl<A: Action, S: BuildHasher + Default> Into<InputRebind<A, S>> for Builder<A, S> {
-    fn into(self) -> InputRebind<A, S> {
-        let mut rebind = InputRebind::new(self.mouse_data.viewport_size);
+impl<A: Action, S: BuildHasher + Default> From<Builder<A, S>> for InputRebind<A, S> {
+    fn from(builder: Builder<A, S>) -> InputRebind<A, S> {
+        let mut rebind = InputRebind::new(builder.mouse_data.viewport_size);
 
-        rebind.mouse_data = self.mouse_data;
-        rebind.keymap = to_act_bt_hashmap(self.input_remappings.iter().cloned());
+        rebind.mouse_data = builder.mouse_data;
+        // Build directly from the ordered remappings rather than going through an
+        // `InputTranslator`, whose keymap would lose the insertion order.
+        rebind.keymap = to_act_bt_hashmap(builder.input_remappings.iter().cloned());
+        for &a in &builder.registered_actions {
+            rebind.keymap.entry(a).or_insert_with(ButtonTuple::new);
+        }
 
         rebind
     }
@@ -0,0 +1,190 @@
+use {Action, ButtonTuple, InputRebind, RebindError};
+use input::Button;
+use std::cmp::{self, Ordering};
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A change to the bindings of a single action, recorded by a `RebindJournal`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JournalEntry<A: Action> {
+    /// The time of the change, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+
+    /// The action whose bindings were changed.
+    pub action: A,
+
+    /// The new bindings of the action, or `None` if the action was removed.
+    pub buttons: Option<ButtonTuple>
+}
+
+/// A wrapper around an `InputRebind` which records a timestamped entry for every change
+/// made through it, e.g. to resolve conflicts between settings synced from several
+/// machines. Timestamps never decrease, even if the system clock is moved backwards.
+///
+/// Every method of `InputRebind` which changes the bindings has a journaled counterpart
+/// here. An entry holds the new bindings of an action, or `None` if the action was removed,
+/// e.g. by `rename_action` or `set_bindings`, so that replaying the journal removes it too.
+#[derive(Clone, Debug)]
+pub struct RebindJournal<A: Action, S: BuildHasher = RandomState> {
+    rebind: InputRebind<A, S>,
+    entries: Vec<JournalEntry<A>>
+}
+
+impl<A: Action, S: BuildHasher + Default> RebindJournal<A, S> {
+    /// Creates a journal with no entries which wraps an `InputRebind`.
+    pub fn new(rebind: InputRebind<A, S>) -> Self {
+        RebindJournal {
+            rebind,
+            entries: vec![]
+        }
+    }
+
+    /// Returns the wrapped `InputRebind`.
+    pub fn get_rebind(&self) -> &InputRebind<A, S> {
+        &self.rebind
+    }
+
+    /// Unwraps the `InputRebind`, discarding the journal entries.
+    pub fn into_rebind(self) -> InputRebind<A, S> {
+        self.rebind
+    }
+
+    /// Returns every entry in the journal, oldest first.
+    pub fn entries(&self) -> &[JournalEntry<A>] {
+        &self.entries
+    }
+
+    /// Returns the entries recorded at or after `timestamp`, oldest first.
+    pub fn since(&self, timestamp: u64) -> &[JournalEntry<A>] {
+        let start = self.entries.iter().position(|e| e.timestamp >= timestamp).unwrap_or(self.entries.len());
+        &self.entries[start..]
+    }
+
+    /// Calls `InputRebind::insert_action`, and records the change.
+    pub fn insert_action(&mut self, action: A) -> Option<ButtonTuple> {
+        let old = self.rebind.insert_action(action);
+        self.record(action);
+        old
+    }
+
+    /// Calls `InputRebind::try_insert_action`, and records the change if the action was
+    /// inserted.
+    pub fn try_insert_action(&mut self, action: A) -> bool {
+        let inserted = self.rebind.try_insert_action(action);
+        if inserted {
+            self.record(action);
+        }
+        inserted
+    }
+
+    /// Calls `InputRebind::insert_action_with_buttons`, and records the change.
+    pub fn insert_action_with_buttons(&mut self,
+                                      action: A,
+                                      buttons: ButtonTuple)
+                                      -> Result<Option<ButtonTuple>, RebindError> {
+        let old = self.rebind.insert_action_with_buttons(action, buttons)?;
+        self.record(action);
+        Ok(old)
+    }
+
+    /// Calls `InputRebind::add_button`, and records the change if the button was added.
+    pub fn add_button(&mut self, action: A, button: Button) -> Result<(), RebindError> {
+        let before = self.rebind.get_bindings(&action).cloned();
+        self.rebind.add_button(action, button)?;
+        if self.rebind.get_bindings(&action).cloned() != before {
+            self.record(action);
+        }
+        Ok(())
+    }
+
+    /// Calls `InputRebind::add_buttons`, and records the change if any buttons were added.
+    pub fn add_buttons(&mut self, action: A, buttons: ButtonTuple) -> Result<(), usize> {
+        let before = self.rebind.get_bindings(&action).cloned();
+        self.rebind.add_buttons(action, buttons)?;
+        if self.rebind.get_bindings(&action).cloned() != before {
+            self.record(action);
+        }
+        Ok(())
+    }
+
+    /// Calls `InputRebind::replace_button_everywhere`, and records a change for every
+    /// action which was modified.
+    pub fn replace_button_everywhere(&mut self, old: Button, new: Button) -> Result<usize, RebindError> {
+        let modified = self.rebind
+                           .actions()
+                           .into_iter()
+                           .filter(|a| self.rebind.get_bindings(a).is_some_and(|bt| bt.contains(old)))
+                           .collect::<Vec<_>>();
+        let count = self.rebind.replace_button_everywhere(old, new)?;
+        for a in modified {
+            self.record(a);
+        }
+        Ok(count)
+    }
+
+    /// Calls `InputRebind::rename_action`, and records the removal of `from` and the new
+    /// bindings of `to` if the action was renamed.
+    pub fn rename_action(&mut self, from: &A, to: A) -> bool {
+        let renamed = self.rebind.rename_action(from, to);
+        if renamed && *from != to {
+            self.record(*from);
+            self.record(to);
+        }
+        renamed
+    }
+
+    /// Calls `InputRebind::set_bindings`, and records a change for every action whose
+    /// bindings are different afterwards, including the actions which were removed.
+    pub fn set_bindings(&mut self, bindings: HashMap<A, ButtonTuple, S>) -> Result<(), RebindError> {
+        let before = self.snapshot();
+        self.rebind.set_bindings(bindings)?;
+        self.record_changes(before);
+        Ok(())
+    }
+
+    /// Calls `InputRebind::resolve_conflicts_by`, and records a change for every action
+    /// which lost a button.
+    pub fn resolve_conflicts_by<F: Fn(&A, &A) -> Ordering>(&mut self, f: F) {
+        let before = self.snapshot();
+        self.rebind.resolve_conflicts_by(f);
+        self.record_changes(before);
+    }
+
+    fn snapshot(&self) -> Vec<(A, ButtonTuple)> {
+        self.rebind.actions().into_iter().filter_map(|a| self.rebind.get_bindings(&a).map(|&bt| (a, bt))).collect()
+    }
+
+    fn record_changes(&mut self, before: Vec<(A, ButtonTuple)>) {
+        let mut actions = self.rebind.actions();
+        for &(a, _) in &before {
+            if !actions.contains(&a) {
+                actions.push(a);
+            }
+        }
+        actions.sort();
+
+        for a in actions {
+            let old = before.iter().find(|&&(b, _)| b == a).map(|&(_, bt)| bt);
+            if self.rebind.get_bindings(&a).cloned() != old {
+                self.record(a);
+            }
+        }
+    }
+
+    fn record(&mut self, action: A) {
+        let buttons = self.rebind.get_bindings(&action).cloned();
+        let now = SystemTime::now()
+                      .duration_since(UNIX_EPOCH)
+                      .map(|d| d.as_secs() * 1000 + u64::from(d.subsec_millis()))
+                      .unwrap_or(0);
+        let timestamp = self.entries.last().map_or(now, |e| cmp::max(e.timestamp, now));
+
+        self.entries.push(JournalEntry {
+            timestamp,
+            action,
+            buttons
+        });
+    }
+}
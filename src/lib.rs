@@ -38,7 +38,7 @@
 //!         .build()
 //!         .expect("Could not create window");
 //!
-//!     let translator = Builder::<MyAction>::new((800, 600))
+//!     let mut translator = Builder::<MyAction>::new((800, 600))
 //!         .with_mapping(MyAction::Action1, Keyboard(Key::D1))
 //!         .with_mapping(MyAction::Action1, Keyboard(Key::A))
 //!         .with_mapping(MyAction::Action2, Keyboard(Key::D2))
@@ -67,31 +67,71 @@
 extern crate fnv;
 extern crate input;
 extern crate itertools;
+#[cfg(feature = "proptest")]
+#[macro_use]
+extern crate proptest;
 extern crate rustc_serialize;
 extern crate viewport;
 extern crate window;
 
+mod alias;
 mod builder;
+mod journal;
+mod modifier;
+mod profile;
+mod stack;
+mod static_translator;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+mod text;
+mod transaction;
 
-use input::{Button, Input, Motion};
+use input::{Button, EventId, Input, Motion};
 use itertools::Itertools;
-use std::cmp::{Eq, Ord, PartialEq};
-use std::collections::HashMap;
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
+use rustc_serialize::json::{self, Json};
+use std::any::Any;
+use std::cmp::{Eq, Ord, Ordering, PartialEq};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::collections::hash_map::RandomState;
-use std::convert::Into;
+use std::convert::{Into, TryFrom};
 use std::default::Default;
-use std::fmt::{Debug, Formatter, Result};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter, Result};
 use std::hash::{Hash, BuildHasher};
+use std::ops::Index;
+use std::sync::Arc;
+use std::time::Duration;
 use viewport::Viewport;
 use window::Size;
 
+pub use alias::AliasTable;
 pub use builder::Builder;
+pub use journal::{JournalEntry, RebindJournal};
+pub use modifier::{ModifiedButton, ModifierMask};
+pub use profile::{MouseProfileStore, MouseSettings};
+pub use stack::{ContextMode, InputStack};
+pub use static_translator::{OrderedButton, StaticTranslator};
+pub use text::{ParseError, button_from_string, button_to_string};
+pub use transaction::Transaction;
 
 /// Represents a logical action to be bound to a particular button press, e.g.
 /// jump, attack, or move forward. Needs to be hashable, as it is used as a
 /// lookup key when rebinding an action to a different button.
 pub trait Action: Copy + Hash + Ord { }
 
+/// An action with a fixed numeric id, used instead of its position in the action type when
+/// serializing by index, so that reordering the variants of an action enum doesn't change
+/// which action saved data refers to.
+pub trait StableAction: Action {
+    /// Returns the id of this action. Each action must have a different id, which must
+    /// never change once data using it has been saved.
+    fn stable_id(&self) -> u32;
+
+    /// Returns the action with the given id, or `None` if there is no such action.
+    fn from_stable_id(id: u32) -> Option<Self>;
+}
+
 /// A translated action.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Translated<A: Action> {
@@ -103,8 +143,83 @@ pub enum Translated<A: Action> {
 
     /// A translated mouse motion. The logical origin of a translated MouseCursor event
     /// is in the top left corner of the window, and the logical scroll is non-natural.
-    /// Relative events are unchanged for now.
-    Move(Motion)
+    /// Relative events are negated along each inverted axis.
+    Move(Motion),
+
+    /// The value of an axis made from two opposing actions, which were bound using
+    /// `InputTranslator::bind_axis`. The value is `-1.0` when only the negative action is
+    /// held, `1.0` when only the positive action is held, and `0.0` otherwise.
+    Axis(u32, f64)
+}
+
+impl<A: Action> Translated<A> {
+    /// Convert this event into a record which refers to its action by index, e.g. for
+    /// recording a stream of translated events so that it can be replayed later. The index
+    /// of each action is given by `index_of`.
+    pub fn to_record<F: Fn(&A) -> u32>(&self, index_of: F) -> TranslatedRecord {
+        match *self {
+            Translated::Press(ref a) => TranslatedRecord::Press(index_of(a)),
+            Translated::Release(ref a) => TranslatedRecord::Release(index_of(a)),
+            Translated::Move(motion) => TranslatedRecord::Move(motion),
+            Translated::Axis(id, value) => TranslatedRecord::Axis(id, value),
+        }
+    }
+
+    /// Reconstruct an event from a record created by `to_record`. The action for each
+    /// index is given by `action_of`, and `None` is returned if the index has no action.
+    pub fn from_record<F: Fn(u32) -> Option<A>>(record: &TranslatedRecord, action_of: F) -> Option<Self> {
+        match *record {
+            TranslatedRecord::Press(i) => action_of(i).map(Translated::Press),
+            TranslatedRecord::Release(i) => action_of(i).map(Translated::Release),
+            TranslatedRecord::Move(motion) => Some(Translated::Move(motion)),
+            TranslatedRecord::Axis(id, value) => Some(Translated::Axis(id, value)),
+        }
+    }
+}
+
+impl<A: StableAction> Translated<A> {
+    /// Convert this event into a record which refers to its action by its stable id.
+    pub fn to_stable_record(&self) -> TranslatedRecord {
+        self.to_record(A::stable_id)
+    }
+
+    /// Reconstruct an event from a record created by `to_stable_record`. Returns `None` if
+    /// no action has the record's id.
+    pub fn from_stable_record(record: &TranslatedRecord) -> Option<Self> {
+        Translated::from_record(record, A::from_stable_id)
+    }
+}
+
+impl<A: Action + Display> Display for Translated<A> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            Translated::Press(ref a) => write!(f, "Press({})", a),
+            Translated::Release(ref a) => write!(f, "Release({})", a),
+            Translated::Move(Motion::MouseCursor(x, y)) => write!(f, "Move(MouseCursor({}, {}))", x, y),
+            Translated::Move(Motion::MouseRelative(dx, dy)) => write!(f, "Move(MouseRelative({}, {}))", dx, dy),
+            Translated::Move(Motion::MouseScroll(x, y)) => write!(f, "Move(MouseScroll({}, {}))", x, y),
+            Translated::Move(ref motion) => write!(f, "Move({:?})", motion),
+            Translated::Axis(id, value) => write!(f, "Axis({}, {})", id, value),
+        }
+    }
+}
+
+/// A `Translated` event which refers to its action by index rather than by value, so
+/// that it can be stored independently of the action type. Created by
+/// `Translated::to_record`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TranslatedRecord {
+    /// A press of the action with the given index
+    Press(u32),
+
+    /// A release of the action with the given index
+    Release(u32),
+
+    /// A translated mouse motion
+    Move(Motion),
+
+    /// The value of an axis
+    Axis(u32, f64)
 }
 
 /// A three-element tuple of `Option<Button>`. For simplicity, a maximum number of 3
@@ -129,21 +244,28 @@ impl ButtonTuple {
     /// If the button is inserted, returns true. Otherwise, if the button is not inserted,
     /// this function returns false.
     pub fn insert_inplace(&mut self, button: Button) -> bool {
+        self.insert_at_first_free(button).is_some()
+    }
+
+    /// Insert a button into the first free slot of the tuple, searching from left to right.
+    /// Returns the index of the slot which the button was inserted into, or `None` if the
+    /// tuple is full.
+    pub fn insert_at_first_free(&mut self, button: Button) -> Option<usize> {
         let sbtn = Some(button);
-        match self {
-            &mut ButtonTuple(None, _, _) => {
+        match *self {
+            ButtonTuple(None, _, _) => {
                 self.0 = sbtn;
-                true
+                Some(0)
             }
-            &mut ButtonTuple(_, None, _) => {
+            ButtonTuple(_, None, _) => {
                 self.1 = sbtn;
-                true
+                Some(1)
             }
-            &mut ButtonTuple(_, _, None) => {
+            ButtonTuple(_, _, None) => {
                 self.2 = sbtn;
-                true
+                Some(2)
             }
-            _ => false,
+            _ => None,
         }
     }
 
@@ -154,13 +276,105 @@ impl ButtonTuple {
 
     /// Returns the number of buttons in the ButtonTuple which are not `None`.
     pub fn num_buttons_set(&self) -> usize {
-        self.iter().map(|b| b.is_some() as usize).fold(0, std::ops::Add::add)
+        self.iter().filter(Option::is_some).count()
     }
 
     /// Returns an iterator over this tuple.
     pub fn iter(&self) -> ButtonTupleIter {
         (*self).into_iter()
     }
+
+    /// Returns an iterator over the buttons in the occupied slots, from left to right.
+    fn buttons(&self) -> std::iter::Flatten<ButtonTupleIter> {
+        Iterator::flatten(self.iter())
+    }
+
+    /// Returns each slot of the tuple paired with its index, e.g. for rendering three
+    /// labelled slots in a rebinding UI.
+    pub fn enumerated(&self) -> [(usize, Option<Button>); 3] {
+        [(0, self.0), (1, self.1), (2, self.2)]
+    }
+
+    /// Returns a tuple containing the buttons which are in `self` or in `other`. The
+    /// buttons from `self` come first, followed by the buttons from `other` which are not
+    /// already present. A tuple can only hold 3 buttons, so any buttons which do not fit
+    /// are discarded.
+    pub fn union(&self, other: &ButtonTuple) -> ButtonTuple {
+        let mut buttons = self.buttons().collect_vec();
+        for b in other.buttons() {
+            if !buttons.contains(&b) {
+                buttons.push(b);
+            }
+        }
+        ButtonTuple::from_buttons(buttons)
+    }
+
+    /// Returns a tuple containing the buttons in `self` which are also in `other`.
+    pub fn intersection(&self, other: &ButtonTuple) -> ButtonTuple {
+        ButtonTuple::from_buttons(self.buttons().filter(|&b| other.contains(b)))
+    }
+
+    /// Returns a tuple containing the buttons in `self` which are not in `other`.
+    pub fn difference(&self, other: &ButtonTuple) -> ButtonTuple {
+        ButtonTuple::from_buttons(self.buttons().filter(|&b| !other.contains(b)))
+    }
+
+    /// Returns true if both tuples contain the same buttons, ignoring the slots which they
+    /// are in.
+    fn same_buttons(&self, other: &ButtonTuple) -> bool {
+        self.difference(other) == ButtonTuple::new() && other.difference(self) == ButtonTuple::new()
+    }
+
+    /// Creates a tuple from the first 3 buttons yielded by `buttons`, searching from left
+    /// to right.
+    fn from_buttons<I: IntoIterator<Item = Button>>(buttons: I) -> ButtonTuple {
+        let mut bt = ButtonTuple::new();
+        for b in buttons.into_iter().take(3) {
+            bt.insert_inplace(b);
+        }
+        bt
+    }
+}
+
+impl Encodable for ButtonTuple {
+    fn encode<E: Encoder>(&self, e: &mut E) -> std::result::Result<(), E::Error> {
+        (self.0, self.1, self.2).encode(e)
+    }
+}
+
+impl Decodable for ButtonTuple {
+    fn decode<D: Decoder>(d: &mut D) -> std::result::Result<Self, D::Error> {
+        let (b0, b1, b2) = Decodable::decode(d)?;
+        Ok(ButtonTuple(b0, b1, b2))
+    }
+}
+
+impl From<ButtonTuple> for Vec<Button> {
+    /// Returns the buttons in the occupied slots of the tuple, from left to right.
+    fn from(bt: ButtonTuple) -> Self {
+        bt.buttons().collect()
+    }
+}
+
+impl TryFrom<Vec<Button>> for ButtonTuple {
+    type Error = usize;
+
+    /// Creates a tuple with the buttons in its leftmost slots. Fails with the number of
+    /// buttons which would not fit if there are more than 3 buttons.
+    fn try_from(buttons: Vec<Button>) -> std::result::Result<Self, usize> {
+        if buttons.len() > 3 {
+            return Err(buttons.len() - 3);
+        }
+        let mut bt = ButtonTuple::new();
+        for (i, b) in buttons.into_iter().enumerate() {
+            match i {
+                0 => bt.0 = Some(b),
+                1 => bt.1 = Some(b),
+                _ => bt.2 = Some(b),
+            }
+        }
+        Ok(bt)
+    }
 }
 
 impl IntoIterator for ButtonTuple {
@@ -172,6 +386,24 @@ impl IntoIterator for ButtonTuple {
     }
 }
 
+impl Index<usize> for ButtonTuple {
+    type Output = Option<Button>;
+
+    /// Returns the button in the given slot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not less than 3.
+    fn index(&self, idx: usize) -> &Option<Button> {
+        match idx {
+            0 => &self.0,
+            1 => &self.1,
+            2 => &self.2,
+            _ => panic!("ButtonTuple index out of bounds: the len is 3 but the index is {}", idx),
+        }
+    }
+}
+
 /// An iterator over a ButtonTuple.
 #[derive(Clone, Copy, Debug)]
 pub struct ButtonTupleIter {
@@ -196,15 +428,178 @@ impl Iterator for ButtonTupleIter {
 
 impl ExactSizeIterator for ButtonTupleIter {
     fn len(&self) -> usize {
-        (std::cmp::min(self.i as isize, 3) - 3).abs() as usize
+        3 - std::cmp::min(self.i, 3)
+    }
+}
+
+/// The kind of device which produced an input.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Device {
+    /// The keyboard.
+    Keyboard,
+
+    /// The mouse.
+    Mouse,
+
+    /// The controller with the given id.
+    Controller(u32),
+
+    /// The application-defined source of custom events with the given id.
+    Custom(EventId)
+}
+
+impl Device {
+    /// Returns the device which a button belongs to.
+    pub fn from_button(button: &Button) -> Device {
+        match *button {
+            Button::Keyboard(_) => Device::Keyboard,
+            Button::Mouse(_) => Device::Mouse,
+            Button::Controller(controller_button) => Device::Controller(controller_button.id),
+        }
+    }
+
+    /// Returns the device which produced a motion.
+    pub fn from_motion(motion: &Motion) -> Device {
+        match *motion {
+            Motion::ControllerAxis(args) => Device::Controller(args.id),
+            _ => Device::Mouse,
+        }
+    }
+}
+
+/// A direction of mouse scrolling, which can be mapped to an action.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ScrollDir {
+    /// A positive scroll along the y axis.
+    Up,
+
+    /// A negative scroll along the y axis.
+    Down,
+
+    /// A negative scroll along the x axis.
+    Left,
+
+    /// A positive scroll along the x axis.
+    Right
+}
+
+impl ScrollDir {
+    /// Returns the opposite direction.
+    pub fn opposite(&self) -> ScrollDir {
+        match *self {
+            ScrollDir::Up => ScrollDir::Down,
+            ScrollDir::Down => ScrollDir::Up,
+            ScrollDir::Left => ScrollDir::Right,
+            ScrollDir::Right => ScrollDir::Left,
+        }
+    }
+
+    /// Returns the direction of one axis of an untranslated scroll, if it moved at all.
+    fn from_axis(value: f64, positive: ScrollDir, negative: ScrollDir) -> Option<ScrollDir> {
+        if value > 0.0 {
+            Some(positive)
+        } else if value < 0.0 {
+            Some(negative)
+        } else {
+            None
+        }
+    }
+}
+
+impl Encodable for ScrollDir {
+    fn encode<E: Encoder>(&self, e: &mut E) -> std::result::Result<(), E::Error> {
+        let (name, idx) = match *self {
+            ScrollDir::Up => ("Up", 0),
+            ScrollDir::Down => ("Down", 1),
+            ScrollDir::Left => ("Left", 2),
+            ScrollDir::Right => ("Right", 3),
+        };
+        e.emit_enum("ScrollDir", |e| e.emit_enum_variant(name, idx, 0, |_| Ok(())))
+    }
+}
+
+impl Decodable for ScrollDir {
+    fn decode<D: Decoder>(d: &mut D) -> std::result::Result<Self, D::Error> {
+        d.read_enum("ScrollDir", |d| {
+            d.read_enum_variant(&["Up", "Down", "Left", "Right"], |d, idx| {
+                match idx {
+                    0 => Ok(ScrollDir::Up),
+                    1 => Ok(ScrollDir::Down),
+                    2 => Ok(ScrollDir::Left),
+                    3 => Ok(ScrollDir::Right),
+                    _ => Err(d.error("unknown ScrollDir variant")),
+                }
+            })
+        })
+    }
+}
+
+/// Returns a human readable name for a button, which is suitable for displaying in a
+/// list of controls.
+pub fn button_display_name(button: &Button) -> String {
+    match *button {
+        Button::Keyboard(key) => format!("{:?}", key),
+        Button::Mouse(mouse_button) => format!("Mouse {:?}", mouse_button),
+        Button::Controller(controller_button) => {
+            format!("Controller {} Button {}", controller_button.id, controller_button.button)
+        }
+    }
+}
+
+/// The size of the viewport used for calculating mouse positions, as `(width, height)`.
+/// This is distinct from `Size` so that other dimensions, such as the size of a texture,
+/// can't accidentally be used where the viewport size is expected.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ViewportSize(pub u32, pub u32);
+
+impl From<Size> for ViewportSize {
+    fn from(size: Size) -> Self {
+        ViewportSize(size.width, size.height)
+    }
+}
+
+impl From<(u32, u32)> for ViewportSize {
+    fn from((width, height): (u32, u32)) -> Self {
+        ViewportSize(width, height)
+    }
+}
+
+impl From<[u32; 2]> for ViewportSize {
+    fn from(size: [u32; 2]) -> Self {
+        ViewportSize(size[0], size[1])
+    }
+}
+
+impl From<ViewportSize> for Size {
+    fn from(ViewportSize(width, height): ViewportSize) -> Self {
+        Size { width, height }
     }
 }
 
 /// An object which translates piston::input::Input events into input_map::Translated<A> events
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct InputTranslator<A: Action, S: BuildHasher = RandomState> {
     keymap: HashMap<Button, A, S>,
-    mouse_translator: MouseTranslator
+    modified_keymap: HashMap<ModifiedButton, A, S>,
+    mouse_translator: MouseTranslator,
+    held_buttons: HashSet<Button, S>,
+    modified_presses: Vec<(Button, A)>,
+    pressed_actions: Vec<(Button, A)>,
+    last_device: Option<Device>,
+    scroll_remainder: f64,
+    axes: Vec<(u32, A, A)>,
+    any_button_action: Option<A>,
+    release_on_last_button: bool,
+    debounce: Duration,
+    pending_releases: Vec<(Button, Duration)>,
+    tap_hold_keymap: Vec<(Button, A, A, Duration)>,
+    tap_hold_presses: Vec<(Button, Duration, bool)>,
+    scroll_keymap: Vec<(ScrollDir, A, bool)>,
+    custom_handlers: CustomHandlers<A>,
+    paused: bool,
+    keyboard_enabled: bool,
+    mouse_enabled: bool,
+    controller_enabled: bool
 }
 
 impl<A: Action, S: BuildHasher + Default> InputTranslator<A, S> {
@@ -212,36 +607,642 @@ impl<A: Action, S: BuildHasher + Default> InputTranslator<A, S> {
     pub fn new<Sz: Into<Size>>(size: Sz) -> Self {
         InputTranslator {
             keymap: HashMap::<_, _, S>::default(),
-            mouse_translator: MouseTranslator::new(size)
+            modified_keymap: HashMap::<_, _, S>::default(),
+            mouse_translator: MouseTranslator::new(size),
+            held_buttons: HashSet::<_, S>::default(),
+            modified_presses: vec![],
+            pressed_actions: vec![],
+            last_device: None,
+            scroll_remainder: 0.0,
+            axes: vec![],
+            any_button_action: None,
+            release_on_last_button: false,
+            debounce: Duration::from_secs(0),
+            pending_releases: vec![],
+            tap_hold_keymap: vec![],
+            tap_hold_presses: vec![],
+            scroll_keymap: vec![],
+            custom_handlers: CustomHandlers(vec![]),
+            paused: false,
+            keyboard_enabled: true,
+            mouse_enabled: true,
+            controller_enabled: true
         }
     }
 
     /// Translate an Input into a Translated<A> event. Returns `None` if there is no
-    /// action associated with the `Input` variant.
-    pub fn translate(&self, input: &Input) -> Option<Translated<A>> {
-        macro_rules! translate_button(($but_state:ident, $but_var:ident) => (
-            match self.keymap.get(&$but_var).cloned() {
-                Some(act) => Some(Translated::$but_state(act)),
-                None => None
-            });
-        );
-
-        match input {
-            &Input::Press(button) => translate_button!(Press, button),
-            &Input::Release(button) => translate_button!(Release, button),
-            &Input::Move(motion) => Some(Translated::Move(self.mouse_translator.translate(motion))),
+    /// action associated with the `Input` variant, or if translation is paused. Presses
+    /// and releases of actions which are part of an axis are translated into
+    /// `Translated::Axis` events.
+    pub fn translate(&mut self, input: &Input) -> Option<Translated<A>> {
+        // Button events are handled separately from the mouse, so that they never touch
+        // the `MouseTranslator`. The held buttons and last device are tracked even while
+        // translation is paused, so that they are still correct after it is resumed.
+        match *input {
+            Input::Press(button) => {
+                self.held_buttons.insert(button);
+                self.last_device = Some(Device::from_button(&button));
+                self.translate_button(button, true)
+            }
+            Input::Release(button) => {
+                self.held_buttons.remove(&button);
+                self.last_device = Some(Device::from_button(&button));
+                self.translate_button(button, false)
+            }
+            Input::Move(motion) => {
+                self.last_device = Some(Device::from_motion(&motion));
+                if !self.paused && self.motion_enabled(motion) {
+                    if let Motion::MouseScroll(x, y) = motion {
+                        if let Some(a) = self.scroll_action(x, y) {
+                            return Some(self.translate_axis(Translated::Press(a)));
+                        }
+                    }
+                    Some(Translated::Move(self.mouse_translator.translate(motion)))
+                } else {
+                    None
+                }
+            }
+            Input::Custom(id, ref data) if !self.paused => {
+                self.custom_handlers
+                    .get(id)
+                    .and_then(|handler| handler(data))
+                    .map(|translated| self.translate_axis(translated))
+            }
             _ => None,
         }
     }
 
+    /// Translate an Input which happened at `time`, measured from any fixed point such as
+    /// the start of the game. Unlike `translate`, this can return several events, since
+    /// events which were delayed by earlier timed inputs are returned first, once they are
+    /// due. Inputs passed to `translate` instead aren't delayed, and don't release delayed
+    /// events.
+    ///
+    /// A button with a tap/hold mapping translates into a press and release of the tap
+    /// action when it is released within the mapping's threshold. Otherwise, it translates
+    /// into a press of the hold action once the threshold has passed, and a release of the
+    /// hold action when the button is released. These buttons are not debounced.
+    pub fn translate_at(&mut self, input: &Input, time: Duration) -> Vec<Translated<A>> {
+        let mut translated = self.poll_at(time);
+        if let Some(events) = self.translate_tap_hold(input, time) {
+            translated.extend(events);
+            return translated;
+        }
+        if self.debounce > Duration::from_secs(0) {
+            match *input {
+                Input::Release(button) => {
+                    self.pending_releases.push((button, time));
+                    return translated;
+                }
+                Input::Press(button) => {
+                    // The release is still pending, so the button is still held and the
+                    // press doesn't need to be translated either.
+                    if let Some(i) = self.pending_releases.iter().position(|&(b, _)| b == button) {
+                        self.pending_releases.remove(i);
+                        return translated;
+                    }
+                }
+                _ => {}
+            }
+        }
+        translated.extend(self.translate(input));
+        translated
+    }
+
+    /// Returns the events which were delayed by earlier calls to `translate_at`, and are
+    /// due at `time`. This should be called regularly, e.g. once per frame, so that
+    /// delayed events aren't held back until the next input.
+    pub fn poll_at(&mut self, time: Duration) -> Vec<Translated<A>> {
+        let debounce = self.debounce;
+        let is_due = |t: Duration| time.checked_sub(t).is_some_and(|d| d > debounce);
+        let (due, pending): (Vec<_>, _) = self.pending_releases.iter().partition(|&&(_, t)| is_due(t));
+        self.pending_releases = pending;
+        let mut translated = due.into_iter().filter_map(|(b, _)| self.translate(&Input::Release(b))).collect_vec();
+
+        for i in 0..self.tap_hold_presses.len() {
+            let (button, pressed_at, held) = self.tap_hold_presses[i];
+            if held {
+                continue;
+            }
+            if let Some(&(_, _, hold_action, threshold)) = self.tap_hold_keymap.iter().find(|m| m.0 == button) {
+                if time.checked_sub(pressed_at).is_some_and(|d| d >= threshold) {
+                    self.tap_hold_presses[i].2 = true;
+                    translated.push(Translated::Press(hold_action));
+                }
+            }
+        }
+        translated
+    }
+
+    /// Handles a press or release of a button with a tap/hold mapping, returning `None` if
+    /// the input should be translated normally.
+    fn translate_tap_hold(&mut self, input: &Input, time: Duration) -> Option<Vec<Translated<A>>> {
+        let (button, pressed) = match *input {
+            Input::Press(button) => (button, true),
+            Input::Release(button) => (button, false),
+            _ => return None,
+        };
+        let (_, tap_action, hold_action, _) = match self.tap_hold_keymap.iter().find(|m| m.0 == button) {
+            Some(&mapping) => mapping,
+            None => return None,
+        };
+        if self.paused || !self.button_enabled(button) {
+            return None;
+        }
+
+        if pressed {
+            if !self.tap_hold_presses.iter().any(|p| p.0 == button) {
+                self.tap_hold_presses.push((button, time, false));
+            }
+            return Some(vec![]);
+        }
+        match self.tap_hold_presses.iter().position(|p| p.0 == button) {
+            Some(i) => {
+                let (_, _, held) = self.tap_hold_presses.remove(i);
+                Some(if held {
+                    vec![Translated::Release(hold_action)]
+                } else {
+                    vec![Translated::Press(tap_action), Translated::Release(tap_action)]
+                })
+            }
+            None => Some(vec![]),
+        }
+    }
+
+    /// Returns the debounce threshold used by `translate_at`.
+    pub fn get_debounce(&self) -> Duration {
+        self.debounce
+    }
+
+    /// Set the debounce threshold used by `translate_at`, to filter out the spurious
+    /// releases sent by noisy hardware. A release is delayed by up to the threshold, and is
+    /// dropped along with the following press if the same button is pressed again within
+    /// the threshold, so that its action stays active. A threshold of zero, the default,
+    /// disables debouncing.
+    pub fn set_debounce(&mut self, threshold: Duration) {
+        self.debounce = threshold;
+    }
+
+    /// Translate an Input like `translate`, pairing the result with whether the input
+    /// changed the held buttons. The flag is true for the first press of a button and for
+    /// its release, and false for repeated presses, releases of buttons which weren't held,
+    /// and inputs other than presses and releases.
+    pub fn translate_edge(&mut self, input: &Input) -> Option<(Translated<A>, bool)> {
+        let edge = match *input {
+            Input::Press(button) => !self.held_buttons.contains(&button),
+            Input::Release(button) => self.held_buttons.contains(&button),
+            _ => false,
+        };
+        self.translate(input).map(|t| (t, edge))
+    }
+
+    /// Translate an Input like `translate`, pairing the result with the device which
+    /// produced it, so that events can be routed by device. Inputs which don't come from a
+    /// device, such as window events, return `None`. Custom events are paired with
+    /// `Device::Custom`.
+    pub fn translate_with_device(&mut self, input: &Input) -> Option<(Translated<A>, Device)> {
+        let device = match *input {
+            Input::Press(button) | Input::Release(button) => Some(Device::from_button(&button)),
+            Input::Move(motion) => Some(Device::from_motion(&motion)),
+            Input::Custom(id, _) => Some(Device::Custom(id)),
+            _ => None,
+        };
+        let translated = self.translate(input);
+        translated.and_then(|t| device.map(|d| (t, d)))
+    }
+
+    /// Translate an Input like `translate`, but track the held buttons in `held` instead of
+    /// in the translator, for callers which already keep their own set of held buttons. A
+    /// release is only translated once no other held button is bound to the same action.
+    /// Modified mappings are not used, as they need the translator to remember which
+    /// action each modified press triggered, and unrecognized motion is not counted.
+    pub fn translate_with_state(&self, input: &Input, held: &mut HashSet<Button>) -> Option<Translated<A>> {
+        let is_held = |held: &HashSet<Button>, a: &A| held.iter().any(|b| self.keymap.get(b) == Some(a));
+        let translated = match *input {
+            Input::Press(button) => {
+                held.insert(button);
+                if self.paused || !self.button_enabled(button) {
+                    return None;
+                }
+                match self.keymap.get(&button).cloned().or(self.any_button_action) {
+                    Some(a) => Translated::Press(a),
+                    None => return None,
+                }
+            }
+            Input::Release(button) => {
+                held.remove(&button);
+                if self.paused || !self.button_enabled(button) {
+                    return None;
+                }
+                match self.keymap.get(&button) {
+                    Some(a) if !is_held(held, a) => Translated::Release(*a),
+                    _ => return None,
+                }
+            }
+            Input::Move(motion) if !self.paused && self.motion_enabled(motion) => {
+                let scroll_action = match motion {
+                    Motion::MouseScroll(x, y) => self.scroll_action(x, y),
+                    _ => None,
+                };
+                match scroll_action {
+                    Some(a) => Translated::Press(a),
+                    None => return Some(Translated::Move(self.mouse_translator.translate_motion(motion))),
+                }
+            }
+            Input::Custom(id, ref data) if !self.paused => {
+                self.custom_handlers.get(id).and_then(|handler| handler(data))?
+            }
+            _ => return None,
+        };
+        let held = &*held;
+        Some(self.translate_axis_with(translated, |a| is_held(held, a)))
+    }
+
+    /// Returns the device which produced the most recent button or motion input, or
+    /// `None` if no such input has been translated yet. This is useful for showing
+    /// prompts for the device which the player is currently using.
+    pub fn last_device(&self) -> Option<Device> {
+        self.last_device
+    }
+
+    /// Returns true if any of the buttons bound to an action are currently held, from any
+    /// device.
+    pub fn is_action_active(&self, action: &A) -> bool {
+        self.is_action_held(action)
+    }
+
+    /// Returns the buttons bound to an action which are currently held, in no particular
+    /// order.
+    pub fn active_bindings(&self, action: &A) -> Vec<Button> {
+        self.held_buttons.iter().filter(|b| self.keymap.get(b) == Some(action)).cloned().collect()
+    }
+
+    /// Accumulate a scroll delta, and return the whole number of steps to scroll by, e.g. to
+    /// move through a menu one item per wheel notch. The fractional part of the delta is
+    /// kept for the next call, so many small deltas from a high resolution device still
+    /// add up to whole steps.
+    pub fn scroll_step(&mut self, delta: f64) -> i32 {
+        self.scroll_remainder += delta;
+        let steps = self.scroll_remainder.trunc();
+        self.scroll_remainder -= steps;
+        steps as i32
+    }
+
+    /// Returns a bitset of which actions are currently held, where bit `i` is set if the
+    /// action `order[i]` is held. An action is held while any of the buttons bound to it
+    /// are held. Only the first 64 actions in `order` can be represented.
+    pub fn active_bitset(&self, order: &[A]) -> u64 {
+        order.iter()
+             .take(64)
+             .enumerate()
+             .filter(|&(_, a)| self.is_action_held(a))
+             .fold(0, |bits, (i, _)| bits | 1 << i)
+    }
+
+    /// Register a handler which translates `Input::Custom` events with the given id. The
+    /// handler is called with the event's data, and its result is returned from
+    /// `translate`. If there is already a handler for the id, it is replaced.
+    pub fn set_custom_handler(&mut self, id: EventId, handler: CustomHandler<A>) {
+        self.custom_handlers.0.retain(|&(handler_id, _)| handler_id != id);
+        self.custom_handlers.0.push((id, Arc::new(handler)));
+    }
+
+    /// Remove all of the button bindings from this InputTranslator. The mouse settings
+    /// are kept, so mouse motion is still translated.
+    pub fn clear(&mut self) {
+        self.keymap.clear();
+        self.modified_keymap.clear();
+    }
+
+    /// Returns true if this translator binds exactly the same buttons to the same actions
+    /// as an `InputRebind`. The mouse settings are not compared.
+    pub fn same_bindings_as(&self, rebind: &InputRebind<A, S>) -> bool {
+        let mut count = 0;
+        for (&a, bt) in rebind.keymap.iter() {
+            for b in bt.buttons() {
+                if self.keymap.get(&b) != Some(&a) {
+                    return false;
+                }
+                count += 1;
+            }
+        }
+        count == self.keymap.len()
+    }
+
+    /// Returns the number of distinct actions which are bound to at least one button.
+    pub fn distinct_action_count(&self) -> usize {
+        self.keymap.values().chain(self.modified_keymap.values()).collect::<HashSet<_>>().len()
+    }
+
+    /// Release the actions which were pressed by a button that is still held, but which
+    /// is no longer bound to the action, e.g. after `InputRebind::update_translator`. This
+    /// prevents an action from staying active after it is rebound while its button is held.
+    /// Returns a `Translated::Release` for each such action, unless it is still held through
+    /// another button.
+    pub fn reconcile(&mut self) -> Vec<Translated<A>> {
+        let (kept, stale): (Vec<_>, Vec<_>) = {
+            let keymap = &self.keymap;
+            self.pressed_actions.drain(..).partition(|&(b, a)| keymap.get(&b) == Some(&a))
+        };
+        self.pressed_actions = kept;
+
+        let mut released = vec![];
+        for (_, a) in stale {
+            if !released.contains(&a) && !self.is_action_held(&a) {
+                released.push(a);
+            }
+        }
+        released.into_iter().map(|a| self.translate_axis(Translated::Release(a))).collect()
+    }
+
+    /// Keep only the button bindings for which `f` returns true, e.g. to remove every
+    /// controller binding. Bindings which require modifier keys are passed to `f` with
+    /// their button.
+    pub fn retain_bindings<F: FnMut(&Button, &A) -> bool>(&mut self, mut f: F) {
+        self.keymap.retain(|b, a| f(b, a));
+        self.modified_keymap.retain(|mb, a| f(&mb.button, a));
+    }
+
+    /// Combine two opposing actions into an axis, so that pressing and releasing either
+    /// action is translated into a `Translated::Axis(axis_id, value)` event instead of a
+    /// press or release. If there is already an axis with the same id, it is replaced.
+    pub fn bind_axis(&mut self, axis_id: u32, neg_action: A, pos_action: A) {
+        self.axes.retain(|&(id, _, _)| id != axis_id);
+        self.axes.push((axis_id, neg_action, pos_action));
+    }
+
+    /// Returns whether translation is paused.
+    pub fn get_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause or resume translation. While paused, every input translates to `None`, but
+    /// the bindings and mouse settings are kept.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Returns the action which is triggered by pressing any button which is not bound
+    /// to another action.
+    pub fn get_any_button_action(&self) -> Option<A> {
+        self.any_button_action
+    }
+
+    /// Set a catch-all action which is triggered when any button without a binding of its
+    /// own is pressed, e.g. for a "press any key to continue" screen. Buttons which are
+    /// bound to an action still translate to that action. Pass `None` to remove the
+    /// catch-all action.
+    pub fn set_any_button_action(&mut self, action: Option<A>) {
+        self.any_button_action = action;
+    }
+
+    /// Returns whether an action is only released once every button bound to it is released.
+    pub fn get_release_on_last_button(&self) -> bool {
+        self.release_on_last_button
+    }
+
+    /// Set whether an action is only released once every button bound to it is released.
+    /// When enabled, releasing one of several held buttons bound to the same action
+    /// translates to `None`, and `Translated::Release` is only returned when the last of
+    /// them is released. This is disabled by default.
+    pub fn set_release_on_last_button(&mut self, enabled: bool) {
+        self.release_on_last_button = enabled;
+    }
+
+    /// Returns whether keyboard buttons are translated.
+    pub fn get_keyboard_enabled(&self) -> bool {
+        self.keyboard_enabled
+    }
+
+    /// Set whether keyboard buttons are translated. When disabled, keyboard input
+    /// translates to `None`, but the keyboard bindings are kept.
+    pub fn set_keyboard_enabled(&mut self, enabled: bool) {
+        self.keyboard_enabled = enabled;
+    }
+
+    /// Returns whether mouse buttons and mouse motion are translated.
+    pub fn get_mouse_enabled(&self) -> bool {
+        self.mouse_enabled
+    }
+
+    /// Set whether mouse buttons and mouse motion are translated. When disabled, mouse
+    /// input translates to `None`, but the mouse bindings are kept.
+    pub fn set_mouse_enabled(&mut self, enabled: bool) {
+        self.mouse_enabled = enabled;
+    }
+
+    /// Returns whether controller buttons and axes are translated.
+    pub fn get_controller_enabled(&self) -> bool {
+        self.controller_enabled
+    }
+
+    /// Set whether controller buttons and axes are translated. When disabled, controller
+    /// input translates to `None`, but the controller bindings are kept.
+    pub fn set_controller_enabled(&mut self, enabled: bool) {
+        self.controller_enabled = enabled;
+    }
+
+    #[inline]
+    fn translate_button(&mut self, button: Button, pressed: bool) -> Option<Translated<A>> {
+        if !pressed && !self.pressed_actions.is_empty() {
+            self.pressed_actions.retain(|&(b, _)| b != button);
+        }
+
+        // A button which was pressed with modifiers releases the same action, even if the
+        // modifiers were released first.
+        if !pressed && !self.modified_presses.is_empty() {
+            if let Some(i) = self.modified_presses.iter().position(|&(b, _)| b == button) {
+                let (_, a) = self.modified_presses.swap_remove(i);
+                if self.paused || !self.button_enabled(button) {
+                    return None;
+                }
+                return Some(self.translate_axis(Translated::Release(a)));
+            }
+        }
+
+        if self.paused || !self.button_enabled(button) {
+            return None;
+        }
+
+        if pressed && !self.modified_keymap.is_empty() {
+            let mods = ModifierMask::from_held(&self.held_buttons);
+            if let Some(&a) = self.modified_keymap.get(&ModifiedButton { button, mods }) {
+                if !self.modified_presses.contains(&(button, a)) {
+                    self.modified_presses.push((button, a));
+                }
+                return Some(self.translate_axis(Translated::Press(a)));
+            }
+        }
+
+        let translated = match (self.keymap.get(&button), pressed) {
+            (Some(&a), true) => {
+                if !self.pressed_actions.contains(&(button, a)) {
+                    self.pressed_actions.push((button, a));
+                }
+                Translated::Press(a)
+            }
+            (Some(&a), false) => {
+                // The button has already been removed from the held buttons, so the action
+                // is only still held if another button bound to it is down.
+                if self.release_on_last_button && self.is_action_held(&a) {
+                    return None;
+                }
+                Translated::Release(a)
+            }
+            (None, true) => {
+                match self.any_button_action {
+                    Some(a) => Translated::Press(a),
+                    None => return None,
+                }
+            }
+            (None, false) => return None,
+        };
+        Some(self.translate_axis(translated))
+    }
+
+    /// Returns the action mapped to the direction of a scroll. Each mapping's own inversion
+    /// is applied to the untranslated scroll, so the global scroll inversion doesn't affect
+    /// which action is chosen.
+    fn scroll_action(&self, x: f64, y: f64) -> Option<A> {
+        if self.scroll_keymap.is_empty() {
+            return None;
+        }
+
+        let action_for = |dir: Option<ScrollDir>| {
+            dir.and_then(|dir| {
+                self.scroll_keymap
+                    .iter()
+                    .find(|&&(mapped, _, invert)| if invert { mapped.opposite() == dir } else { mapped == dir })
+                    .map(|&(_, a, _)| a)
+            })
+        };
+
+        action_for(ScrollDir::from_axis(y, ScrollDir::Up, ScrollDir::Down))
+            .or_else(|| action_for(ScrollDir::from_axis(x, ScrollDir::Right, ScrollDir::Left)))
+    }
+
+    fn translate_axis(&self, translated: Translated<A>) -> Translated<A> {
+        self.translate_axis_with(translated, |a| self.is_action_held(a))
+    }
+
+    fn translate_axis_with<F: Fn(&A) -> bool>(&self, translated: Translated<A>, is_held: F) -> Translated<A> {
+        let action = match translated {
+            Translated::Press(a) | Translated::Release(a) => a,
+            _ => return translated,
+        };
+
+        match self.axes.iter().find(|&&(_, neg, pos)| action == neg || action == pos) {
+            Some(&(id, neg, pos)) => {
+                let held_value = |a| if is_held(&a) { 1.0 } else { 0.0 };
+                Translated::Axis(id, held_value(pos) - held_value(neg))
+            }
+            None => translated,
+        }
+    }
+
+    fn is_action_held(&self, action: &A) -> bool {
+        self.held_buttons.iter().any(|b| self.keymap.get(b) == Some(action))
+    }
+
+    #[inline]
+    fn button_enabled(&self, button: Button) -> bool {
+        match Device::from_button(&button) {
+            Device::Keyboard => self.keyboard_enabled,
+            Device::Mouse => self.mouse_enabled,
+            _ => self.controller_enabled,
+        }
+    }
+
+    #[inline]
+    fn motion_enabled(&self, motion: Motion) -> bool {
+        match Device::from_motion(&motion) {
+            Device::Controller(_) => self.controller_enabled,
+            _ => self.mouse_enabled,
+        }
+    }
+
+    /// Lazily translate each `Input` yielded by `inputs`, skipping any events which cannot
+    /// be translated.
+    pub fn translate_iter<'a, I: Iterator<Item = Input>>(&'a mut self, inputs: I) -> TranslateIter<'a, A, S, I> {
+        TranslateIter { translator: self, inputs }
+    }
+
+    /// Translate an Input into a Translated<A> event, but only if the translated action is
+    /// in `allowed`. Mouse motion is not bound to an action, so it is always translated.
+    pub fn translate_filtered(&mut self, input: &Input, allowed: &HashSet<A>) -> Option<Translated<A>> {
+        match self.translate(input) {
+            Some(Translated::Press(a)) | Some(Translated::Release(a)) if !allowed.contains(&a) => None,
+            translated => translated,
+        }
+    }
+
+    /// Look up the actions which are currently bound to each of the buttons in `buttons`.
+    /// The returned `Vec` has one entry per button, which is `None` if that button is not
+    /// bound to an action.
+    pub fn actions_for_buttons(&self, buttons: &[Button]) -> Vec<Option<A>> {
+        buttons.iter().map(|b| self.keymap.get(b).cloned()).collect()
+    }
+
+    /// Returns the actions of the modified mappings which could still be triggered by
+    /// pressing more buttons while `held` are held, e.g. every action bound to Ctrl+<key>
+    /// while Ctrl is held. A mapping is a candidate if it requires every held modifier key,
+    /// and any held button which is not a modifier key is the mapping's button. The actions
+    /// are sorted and contain no duplicates.
+    pub fn candidate_actions(&self, held: &[Button]) -> Vec<A> {
+        let held_mods = ModifierMask::from_held(held);
+        let mut candidates = self.modified_keymap
+                                 .iter()
+                                 .filter(|&(mb, _)| {
+                                     mb.mods.contains(held_mods) &&
+                                     held.iter()
+                                         .filter(|b| ModifierMask::from_button(b).is_empty())
+                                         .all(|&b| b == mb.button)
+                                 })
+                                 .map(|(_, &a)| a)
+                                 .collect_vec();
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+
+    /// Returns whether unrecognized motion is counted.
+    pub fn get_strict_motion(&self) -> bool {
+        self.mouse_translator.data.strict_motion
+    }
+
+    /// Set whether to check for motion which this crate can't translate, i.e. motion with
+    /// a coordinate or axis position which is NaN or infinite. In strict mode such motion
+    /// is passed through unchanged and counted by `unrecognized_motion_count`, which helps
+    /// to catch a backend or engine upgrade producing motion this crate doesn't handle.
+    pub fn set_strict_motion(&mut self, strict: bool) {
+        self.mouse_translator.data.strict_motion = strict;
+    }
+
+    /// Returns the number of unrecognized motions translated while in strict mode.
+    pub fn unrecognized_motion_count(&self) -> usize {
+        self.mouse_translator.unrecognized_motions
+    }
+
     /// Re-set the mouse bounds size used for calculating mouse events
-    pub fn set_size<Sz: Into<Size>>(&mut self, size: Sz) {
-        self.mouse_translator.data.viewport_size = size.into()
+    pub fn set_size<Sz: Into<ViewportSize>>(&mut self, size: Sz) {
+        self.mouse_translator.data.viewport_size = Size::from(size.into())
+    }
+
+    /// Returns the viewport size used for calculating mouse positions.
+    pub fn viewport_size(&self) -> Size {
+        self.mouse_translator.data.viewport_size
+    }
+
+    /// Re-set the mouse bounds size used for calculating mouse events, returning the
+    /// translator so that calls can be chained.
+    pub fn with_size<Sz: Into<ViewportSize>>(mut self, size: Sz) -> Self {
+        self.set_size(size);
+        self
     }
 
     /// Re-set the mouse bounds size from a viewport
     pub fn set_size_from_viewport(&mut self, vp: Viewport) {
-        self.set_size(Size::from(vp.draw_size));
+        self.set_size(vp.draw_size);
     }
 
     /// Convert the `InputTranslator` into an `InputRebind`. Consumes the
@@ -251,6 +1252,141 @@ impl<A: Action, S: BuildHasher + Default> InputTranslator<A, S> {
     }
 }
 
+/// A function which translates the data of an `Input::Custom` event.
+pub type CustomHandler<A> = Box<dyn Fn(&Arc<dyn Any>) -> Option<Translated<A>> + Send + Sync>;
+
+/// The custom event handlers of an `InputTranslator`. The handlers are reference counted
+/// so that the translator can still be cloned, and two sets of handlers are considered
+/// equal if they handle the same event ids.
+#[derive(Clone)]
+struct CustomHandlers<A: Action>(Vec<(EventId, Arc<CustomHandler<A>>)>);
+
+impl<A: Action> CustomHandlers<A> {
+    fn get(&self, id: EventId) -> Option<&CustomHandler<A>> {
+        self.0.iter().find(|&&(handler_id, _)| handler_id == id).map(|(_, handler)| &**handler)
+    }
+}
+
+impl<A: Action> Debug for CustomHandlers<A> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        f.debug_list().entries(self.0.iter().map(|&(id, _)| id)).finish()
+    }
+}
+
+impl<A: Action> PartialEq for CustomHandlers<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.iter().map(|&(id, _)| id).eq(other.0.iter().map(|&(id, _)| id))
+    }
+}
+
+/// Implemented by hand, because a derived impl would require the hasher to implement
+/// `PartialEq`, which `RandomState` doesn't.
+impl<A: Action, S: BuildHasher> PartialEq for InputTranslator<A, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.keymap == other.keymap && self.modified_keymap == other.modified_keymap &&
+        self.mouse_translator == other.mouse_translator && self.held_buttons == other.held_buttons &&
+        self.modified_presses == other.modified_presses && self.pressed_actions == other.pressed_actions &&
+        self.last_device == other.last_device && self.scroll_remainder == other.scroll_remainder &&
+        self.axes == other.axes && self.any_button_action == other.any_button_action &&
+        self.release_on_last_button == other.release_on_last_button && self.debounce == other.debounce &&
+        self.pending_releases == other.pending_releases && self.tap_hold_keymap == other.tap_hold_keymap &&
+        self.tap_hold_presses == other.tap_hold_presses && self.scroll_keymap == other.scroll_keymap &&
+        self.custom_handlers == other.custom_handlers && self.paused == other.paused &&
+        self.keyboard_enabled == other.keyboard_enabled && self.mouse_enabled == other.mouse_enabled &&
+        self.controller_enabled == other.controller_enabled
+    }
+}
+
+/// Only the configuration of the translator is serialized. Runtime state, such as which
+/// buttons are held, the last device used and whether translation is paused, is skipped,
+/// so a deserialized translator always starts with no buttons held. Custom event handlers
+/// can't be serialized, so they must be registered again after decoding.
+impl<A: Action + Encodable, S: BuildHasher> Encodable for InputTranslator<A, S> {
+    fn encode<E: Encoder>(&self, e: &mut E) -> std::result::Result<(), E::Error> {
+        // Encode the keymap as a list of pairs, because not all encoders support
+        // non-string map keys.
+        let bindings = self.keymap.iter().map(|(&b, &a)| (b, a)).collect_vec();
+        let modified_bindings = self.modified_keymap.iter().map(|(&b, &a)| (b, a)).collect_vec();
+        let debounce = if self.debounce > Duration::from_secs(0) {
+            Some(duration_to_parts(self.debounce))
+        } else {
+            None
+        };
+        let tap_hold_bindings = tap_holds_to_parts(&self.tap_hold_keymap);
+
+        e.emit_struct("InputTranslator", 12, |e| {
+            e.emit_struct_field("keymap", 0, |e| bindings.encode(e))?;
+            e.emit_struct_field("mouse_translator", 1, |e| self.mouse_translator.encode(e))?;
+            e.emit_struct_field("axes", 2, |e| self.axes.encode(e))?;
+            e.emit_struct_field("any_button_action", 3, |e| self.any_button_action.encode(e))?;
+            e.emit_struct_field("keyboard_enabled", 4, |e| self.keyboard_enabled.encode(e))?;
+            e.emit_struct_field("mouse_enabled", 5, |e| self.mouse_enabled.encode(e))?;
+            e.emit_struct_field("controller_enabled", 6, |e| self.controller_enabled.encode(e))?;
+            e.emit_struct_field("release_on_last_button", 7, |e| self.release_on_last_button.encode(e))?;
+            e.emit_struct_field("modified_keymap", 8, |e| modified_bindings.encode(e))?;
+            e.emit_struct_field("debounce", 9, |e| debounce.encode(e))?;
+            e.emit_struct_field("tap_hold_keymap", 10, |e| tap_hold_bindings.encode(e))?;
+            e.emit_struct_field("scroll_keymap", 11, |e| self.scroll_keymap.encode(e))
+        })
+    }
+}
+
+impl<A: Action + Decodable, S: BuildHasher + Default> Decodable for InputTranslator<A, S> {
+    fn decode<D: Decoder>(d: &mut D) -> std::result::Result<Self, D::Error> {
+        d.read_struct("InputTranslator", 12, |d| {
+            let bindings: Vec<(Button, A)> = d.read_struct_field("keymap", 0, Decodable::decode)?;
+            let mouse_translator: MouseTranslator = d.read_struct_field("mouse_translator", 1, Decodable::decode)?;
+
+            let mut translator = InputTranslator::new(mouse_translator.data.viewport_size);
+            translator.keymap = bindings.into_iter().collect();
+            translator.mouse_translator = mouse_translator;
+            translator.axes = d.read_struct_field("axes", 2, Decodable::decode)?;
+            translator.any_button_action = d.read_struct_field("any_button_action", 3, Decodable::decode)?;
+            translator.keyboard_enabled = d.read_struct_field("keyboard_enabled", 4, Decodable::decode)?;
+            translator.mouse_enabled = d.read_struct_field("mouse_enabled", 5, Decodable::decode)?;
+            translator.controller_enabled = d.read_struct_field("controller_enabled", 6, Decodable::decode)?;
+            translator.release_on_last_button = d.read_struct_field("release_on_last_button", 7, Decodable::decode)?;
+            let modified_bindings: Vec<(ModifiedButton, A)> =
+                d.read_struct_field("modified_keymap", 8, Decodable::decode)?;
+            translator.modified_keymap = modified_bindings.into_iter().collect();
+            let debounce: Option<(u64, u32)> = d.read_struct_field("debounce", 9, Decodable::decode)?;
+            translator.debounce = debounce.map_or(Duration::from_secs(0), |(secs, nanos)| Duration::new(secs, nanos));
+            let tap_hold_bindings = d.read_struct_field("tap_hold_keymap", 10, Decodable::decode)?;
+            translator.tap_hold_keymap = tap_holds_from_parts(tap_hold_bindings);
+            translator.scroll_keymap = d.read_struct_field("scroll_keymap", 11, Decodable::decode)?;
+            Ok(translator)
+        })
+    }
+}
+
+/// An iterator which lazily translates `Input` events into `Translated<A>` events,
+/// skipping any events which cannot be translated. Created by
+/// `InputTranslator::translate_iter`.
+#[derive(Debug)]
+pub struct TranslateIter<'a, A: Action + 'a, S: BuildHasher + 'a, I> {
+    translator: &'a mut InputTranslator<A, S>,
+    inputs: I
+}
+
+impl<'a, A: Action, S: BuildHasher + Default, I: Iterator<Item = Input>> Iterator for TranslateIter<'a, A, S, I> {
+    type Item = Translated<A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let translator = &mut *self.translator;
+        self.inputs.by_ref().filter_map(|input| translator.translate(&input)).next()
+    }
+}
+
+/// Feed a script of inputs to a translator in order, returning the result for every input.
+/// Unlike `InputTranslator::translate_iter`, inputs which translate to `None` are kept, so
+/// the result lines up with `inputs`. This is useful for testing the exact behaviour of
+/// a set of bindings, including presses and releases of held buttons.
+pub fn replay<A: Action, S: BuildHasher + Default>(translator: &mut InputTranslator<A, S>,
+                                                     inputs: &[Input])
+                                                     -> Vec<Option<Translated<A>>> {
+    inputs.iter().map(|input| translator.translate(input)).collect()
+}
+
 #[derive(Clone)]
 struct MouseTranslationData {
     x_axis_motion_inverted: bool,
@@ -258,6 +1394,13 @@ struct MouseTranslationData {
     x_axis_scroll_inverted: bool,
     y_axis_scroll_inverted: bool,
     sensitivity: f64,
+    max_delta: Option<f64>,
+    scroll_as_motion: bool,
+    scroll_motion_factor: f64,
+    strict_motion: bool,
+    dpi_scale: f64,
+    normalize_cursor: bool,
+    lock_to_center: bool,
     viewport_size: Size
 }
 
@@ -268,7 +1411,14 @@ impl MouseTranslationData {
             y_axis_motion_inverted: false,
             x_axis_scroll_inverted: false,
             y_axis_scroll_inverted: false,
-            sensitivity: 0.0,
+            sensitivity: 1.0,
+            max_delta: None,
+            scroll_as_motion: false,
+            scroll_motion_factor: 1.0,
+            strict_motion: false,
+            dpi_scale: 1.0,
+            normalize_cursor: false,
+            lock_to_center: false,
             viewport_size: size.into()
         }
     }
@@ -277,12 +1427,19 @@ impl MouseTranslationData {
 impl Debug for MouseTranslationData {
     fn fmt(&self, f: &mut Formatter) -> Result {
         write!(f,
-               "{}, {}, {}, {}, {}, ({}, {})",
+               "{}, {}, {}, {}, {}, {:?}, {}, {}, {}, {}, {}, {}, ({}, {})",
                self.x_axis_motion_inverted,
                self.y_axis_motion_inverted,
                self.x_axis_scroll_inverted,
                self.y_axis_scroll_inverted,
                self.sensitivity,
+               self.max_delta,
+               self.scroll_as_motion,
+               self.scroll_motion_factor,
+               self.strict_motion,
+               self.dpi_scale,
+               self.normalize_cursor,
+               self.lock_to_center,
                self.viewport_size.width,
                self.viewport_size.height)
     }
@@ -294,22 +1451,92 @@ impl PartialEq for MouseTranslationData {
         self.y_axis_motion_inverted == other.y_axis_motion_inverted &&
         self.x_axis_scroll_inverted == other.x_axis_scroll_inverted &&
         self.y_axis_scroll_inverted == other.y_axis_scroll_inverted &&
-        self.sensitivity == other.sensitivity && self.viewport_size.width == other.viewport_size.width &&
+        self.sensitivity == other.sensitivity && self.max_delta == other.max_delta &&
+        self.scroll_as_motion == other.scroll_as_motion &&
+        self.scroll_motion_factor == other.scroll_motion_factor && self.strict_motion == other.strict_motion &&
+        self.dpi_scale == other.dpi_scale && self.normalize_cursor == other.normalize_cursor &&
+        self.lock_to_center == other.lock_to_center &&
+        self.viewport_size.width == other.viewport_size.width &&
         self.viewport_size.height == other.viewport_size.height
     }
 }
 
+impl Encodable for MouseTranslationData {
+    fn encode<E: Encoder>(&self, e: &mut E) -> std::result::Result<(), E::Error> {
+        e.emit_struct("MouseTranslationData", 14, |e| {
+            e.emit_struct_field("x_axis_motion_inverted", 0, |e| self.x_axis_motion_inverted.encode(e))?;
+            e.emit_struct_field("y_axis_motion_inverted", 1, |e| self.y_axis_motion_inverted.encode(e))?;
+            e.emit_struct_field("x_axis_scroll_inverted", 2, |e| self.x_axis_scroll_inverted.encode(e))?;
+            e.emit_struct_field("y_axis_scroll_inverted", 3, |e| self.y_axis_scroll_inverted.encode(e))?;
+            e.emit_struct_field("sensitivity", 4, |e| self.sensitivity.encode(e))?;
+            e.emit_struct_field("max_delta", 5, |e| self.max_delta.encode(e))?;
+            e.emit_struct_field("scroll_as_motion", 6, |e| self.scroll_as_motion.encode(e))?;
+            e.emit_struct_field("scroll_motion_factor", 7, |e| self.scroll_motion_factor.encode(e))?;
+            e.emit_struct_field("strict_motion", 8, |e| self.strict_motion.encode(e))?;
+            e.emit_struct_field("dpi_scale", 9, |e| self.dpi_scale.encode(e))?;
+            e.emit_struct_field("normalize_cursor", 10, |e| self.normalize_cursor.encode(e))?;
+            e.emit_struct_field("lock_to_center", 11, |e| self.lock_to_center.encode(e))?;
+            e.emit_struct_field("viewport_width", 12, |e| self.viewport_size.width.encode(e))?;
+            e.emit_struct_field("viewport_height", 13, |e| self.viewport_size.height.encode(e))
+        })
+    }
+}
+
+impl Decodable for MouseTranslationData {
+    fn decode<D: Decoder>(d: &mut D) -> std::result::Result<Self, D::Error> {
+        d.read_struct("MouseTranslationData", 14, |d| {
+            Ok(MouseTranslationData {
+                x_axis_motion_inverted: d.read_struct_field("x_axis_motion_inverted", 0, Decodable::decode)?,
+                y_axis_motion_inverted: d.read_struct_field("y_axis_motion_inverted", 1, Decodable::decode)?,
+                x_axis_scroll_inverted: d.read_struct_field("x_axis_scroll_inverted", 2, Decodable::decode)?,
+                y_axis_scroll_inverted: d.read_struct_field("y_axis_scroll_inverted", 3, Decodable::decode)?,
+                sensitivity: d.read_struct_field("sensitivity", 4, Decodable::decode)?,
+                max_delta: d.read_struct_field("max_delta", 5, Decodable::decode)?,
+                scroll_as_motion: d.read_struct_field("scroll_as_motion", 6, Decodable::decode)?,
+                scroll_motion_factor: d.read_struct_field("scroll_motion_factor", 7, Decodable::decode)?,
+                strict_motion: d.read_struct_field("strict_motion", 8, Decodable::decode)?,
+                dpi_scale: d.read_struct_field("dpi_scale", 9, Decodable::decode)?,
+                normalize_cursor: d.read_struct_field("normalize_cursor", 10, Decodable::decode)?,
+                lock_to_center: d.read_struct_field("lock_to_center", 11, Decodable::decode)?,
+                viewport_size: Size {
+                    width: d.read_struct_field("viewport_width", 12, Decodable::decode)?,
+                    height: d.read_struct_field("viewport_height", 13, Decodable::decode)?
+                }
+            })
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 struct MouseTranslator {
-    data: MouseTranslationData
+    data: MouseTranslationData,
+    unrecognized_motions: usize
 }
 
 impl MouseTranslator {
     fn new<Sz: Into<Size>>(size: Sz) -> Self {
-        MouseTranslator { data: MouseTranslationData::new(size) }
+        MouseTranslator {
+            data: MouseTranslationData::new(size),
+            unrecognized_motions: 0
+        }
+    }
+
+    #[inline]
+    fn translate(&mut self, motion: Motion) -> Motion {
+        if self.data.strict_motion && !is_finite_motion(&motion) {
+            self.unrecognized_motions += 1;
+        }
+        self.translate_motion(motion)
     }
 
-    fn translate(&self, motion: Motion) -> Motion {
+    /// Translate a motion without counting unrecognized motion, so that it can be used
+    /// through a shared reference.
+    #[inline]
+    fn translate_motion(&self, motion: Motion) -> Motion {
+        if self.data.strict_motion && !is_finite_motion(&motion) {
+            return motion;
+        }
+
         match motion {
             Motion::MouseCursor(x, y) => {
                 let (sw, sh) = {
@@ -317,27 +1544,186 @@ impl MouseTranslator {
                     (width as f64, height as f64)
                 };
 
+                // Cursor positions are continuous coordinates in the inclusive range
+                // `[0, size]`, not pixel indices, so inversion reflects them about the
+                // centre of the viewport: `0` and `size` swap, and `size / 2` is unchanged.
                 let cx = if self.data.x_axis_motion_inverted { sw - x } else { x };
                 let cy = if self.data.y_axis_motion_inverted { sh - y } else { y };
 
-                Motion::MouseCursor(cx, cy)
+                // The cursor and viewport are in physical pixels, so the position is only
+                // normalized or scaled into logical units after inverting. Each axis is
+                // normalized by its own length, so this also holds for a non-square viewport.
+                if self.data.lock_to_center {
+                    self.relative_motion(cx - sw / 2.0, cy - sh / 2.0)
+                } else if self.data.normalize_cursor {
+                    Motion::MouseCursor(cx / sw, cy / sh)
+                } else {
+                    Motion::MouseCursor(cx / self.data.dpi_scale, cy / self.data.dpi_scale)
+                }
             }
             Motion::MouseScroll(x, y) => {
                 let mx = if self.data.x_axis_scroll_inverted { -1.0f64 } else { 1.0 };
                 let my = if self.data.y_axis_scroll_inverted { -1.0f64 } else { 1.0 };
-                Motion::MouseScroll(x * mx, y * my)
+
+                if self.data.scroll_as_motion {
+                    let factor = self.data.scroll_motion_factor;
+                    self.relative_motion(x * mx * factor, y * my * factor)
+                } else {
+                    Motion::MouseScroll(x * mx, y * my)
+                }
+            }
+            Motion::MouseRelative(dx, dy) => {
+                let dx = if self.data.x_axis_motion_inverted { -dx } else { dx };
+                let dy = if self.data.y_axis_motion_inverted { -dy } else { dy };
+                self.relative_motion(dx, dy)
+            }
+            Motion::ControllerAxis(_) => motion,
+        }
+    }
+
+    /// Scale a relative motion by the sensitivity, and then clamp its length to the
+    /// maximum delta. Every relative motion produced by the translator goes through this.
+    fn relative_motion(&self, dx: f64, dy: f64) -> Motion {
+        let (dx, dy) = (dx * self.data.sensitivity, dy * self.data.sensitivity);
+        match self.data.max_delta {
+            Some(max) => {
+                let len = (dx * dx + dy * dy).sqrt();
+                if len > max {
+                    let scale = max / len;
+                    Motion::MouseRelative(dx * scale, dy * scale)
+                } else {
+                    Motion::MouseRelative(dx, dy)
+                }
+            }
+            None => Motion::MouseRelative(dx, dy)
+        }
+    }
+}
+
+/// Utility function to check that every coordinate of a motion is a finite number.
+fn is_finite_motion(motion: &Motion) -> bool {
+    match *motion {
+        Motion::MouseCursor(x, y) | Motion::MouseRelative(x, y) | Motion::MouseScroll(x, y) => {
+            x.is_finite() && y.is_finite()
+        }
+        Motion::ControllerAxis(args) => args.position.is_finite(),
+    }
+}
+
+/// Only the user-configured settings in `data` are serialized. Any state which is built
+/// up while translating motion must be kept out of the encoded form, so that a decoded
+/// `MouseTranslator` always starts from a clean state.
+impl Encodable for MouseTranslator {
+    fn encode<E: Encoder>(&self, e: &mut E) -> std::result::Result<(), E::Error> {
+        self.data.encode(e)
+    }
+}
+
+impl Decodable for MouseTranslator {
+    fn decode<D: Decoder>(d: &mut D) -> std::result::Result<Self, D::Error> {
+        Ok(MouseTranslator {
+            data: Decodable::decode(d)?,
+            unrecognized_motions: 0
+        })
+    }
+}
+
+/// An error which occurred while adding a binding to an `InputRebind`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RebindError {
+    /// The button is already bound to the maximum number of actions.
+    TooManyActions(Button),
+
+    /// The action's `ButtonTuple` has no free slots.
+    NoFreeSlot,
+
+    /// The maximum number of bound buttons has been reached.
+    TooManyBindings
+}
+
+impl Display for RebindError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            RebindError::TooManyActions(button) => {
+                write!(f, "{:?} is already bound to the maximum number of actions", button)
             }
-            relative => relative
+            RebindError::NoFreeSlot => write!(f, "the action has no free button slots"),
+            RebindError::TooManyBindings => write!(f, "the maximum number of bindings has been reached"),
+        }
+    }
+}
+
+impl Error for RebindError {
+    fn description(&self) -> &str {
+        match *self {
+            RebindError::TooManyActions(_) => "button is already bound to the maximum number of actions",
+            RebindError::NoFreeSlot => "the action has no free button slots",
+            RebindError::TooManyBindings => "the maximum number of bindings has been reached",
         }
     }
 }
 
+/// The result of `InputRebind::preview_assign`, describing the effect of assigning a
+/// ButtonTuple to an action.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssignPreview<A: Action> {
+    /// Each button in the ButtonTuple which is already bound to other actions, along with
+    /// those actions.
+    pub conflicts: Vec<(Button, Vec<A>)>,
+
+    /// The buttons which would be bound to more actions than the limit set by
+    /// `InputRebind::set_max_actions_per_button`.
+    pub over_limit: Vec<Button>,
+
+    /// The number of bindings by which the assignment would go over the limit set by
+    /// `InputRebind::set_max_bindings`.
+    pub overflow: usize
+}
+
+impl<A: Action> AssignPreview<A> {
+    /// Returns true if the assignment would not conflict with any other binding, and would
+    /// not go over any of the limits of the InputRebind.
+    pub fn is_clear(&self) -> bool {
+        self.conflicts.is_empty() && self.over_limit.is_empty() && self.overflow == 0
+    }
+}
+
 /// An interface for rebinding keys to actions. This is freely convertable to and
 /// from an InputTranslator.
 #[derive(Clone, Debug, PartialEq)]
 pub struct InputRebind<A: Action, S: BuildHasher = RandomState> {
     keymap: HashMap<A, ButtonTuple, S>,
-    mouse_data: MouseTranslationData
+    mouse_data: MouseTranslationData,
+    max_actions_per_button: Option<usize>,
+    max_bindings: Option<usize>,
+    categories: HashMap<A, String, S>,
+    change_listener: ChangeListenerSlot<A>
+}
+
+/// A function which is called with an action and its new ButtonTuple whenever the
+/// bindings of an `InputRebind` change.
+pub type ChangeListener<A> = Box<dyn FnMut(&A, &ButtonTuple) + Send + Sync>;
+
+/// The change listener of an `InputRebind`. A listener can't be cloned, so a cloned
+/// `InputRebind` has no listener, and listeners are ignored when comparing.
+struct ChangeListenerSlot<A: Action>(Option<ChangeListener<A>>);
+
+impl<A: Action> Clone for ChangeListenerSlot<A> {
+    fn clone(&self) -> Self {
+        ChangeListenerSlot(None)
+    }
+}
+
+impl<A: Action> Debug for ChangeListenerSlot<A> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        f.write_str(if self.0.is_some() { "Some(ChangeListener)" } else { "None" })
+    }
+}
+
+impl<A: Action> PartialEq for ChangeListenerSlot<A> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
 }
 
 impl<A: Action, S: BuildHasher + Default> InputRebind<A, S> {
@@ -345,7 +1731,11 @@ impl<A: Action, S: BuildHasher + Default> InputRebind<A, S> {
     pub fn new<Sz: Into<Size>>(size: Sz) -> Self {
         InputRebind {
             keymap: HashMap::<_, _, S>::default(),
-            mouse_data: MouseTranslationData::new(size)
+            mouse_data: MouseTranslationData::new(size),
+            max_actions_per_button: None,
+            max_bindings: None,
+            categories: HashMap::<_, _, S>::default(),
+            change_listener: ChangeListenerSlot(None)
         }
     }
 
@@ -353,14 +1743,198 @@ impl<A: Action, S: BuildHasher + Default> InputRebind<A, S> {
     /// InputRebind, then its ButtonTuple will be reset to (None, None, None), and
     /// the old ButtonTuple will be returned.
     pub fn insert_action(&mut self, action: A) -> Option<ButtonTuple> {
-        self.keymap.insert(action, ButtonTuple::new())
+        let old = self.keymap.insert(action, ButtonTuple::new());
+        self.notify_change(action);
+        old
+    }
+
+    /// Insert an Action into this InputRebind with an empty ButtonTuple, but only if the
+    /// Action is not already in the InputRebind, so that its buttons are never reset.
+    /// Returns true if the Action was inserted.
+    pub fn try_insert_action(&mut self, action: A) -> bool {
+        if self.keymap.contains_key(&action) {
+            return false;
+        }
+        self.keymap.insert(action, ButtonTuple::new());
+        self.notify_change(action);
+        true
     }
 
     /// Insert an Action into this InputRebind, and assign it to the ButtonTuple.
     /// If the Action is already in the InputRebind, the old ButtonTuple will be
-    /// returned.
-    pub fn insert_action_with_buttons(&mut self, action: A, buttons: ButtonTuple) -> Option<ButtonTuple> {
-        self.keymap.insert(action, buttons)
+    /// returned. Fails, leaving the bindings unchanged, if a button which the action
+    /// doesn't already have is bound to the maximum number of actions, or if the new
+    /// buttons would go over the maximum number of bindings.
+    pub fn insert_action_with_buttons(&mut self,
+                                      action: A,
+                                      buttons: ButtonTuple)
+                                      -> std::result::Result<Option<ButtonTuple>, RebindError> {
+        self.check_limits(&[(action, Some(buttons))])?;
+        let old = self.keymap.insert(action, buttons);
+        self.notify_change(action);
+        Ok(old)
+    }
+
+    /// Add a button to the first free slot of an action's ButtonTuple, inserting the action
+    /// if it is not already in this InputRebind. Adding a button which is already bound to
+    /// the action does nothing. Fails if the action has no free slots, if the button is
+    /// already bound to the maximum number of actions, or if the maximum number of bindings
+    /// has been reached.
+    pub fn add_button(&mut self, action: A, button: Button) -> std::result::Result<(), RebindError> {
+        let mut buttons = self.keymap.get(&action).cloned().unwrap_or_default();
+        if buttons.contains(button) {
+            return Ok(());
+        }
+        if buttons.insert_at_first_free(button).is_none() {
+            return Err(RebindError::NoFreeSlot);
+        }
+
+        self.check_limits(&[(action, Some(buttons))])?;
+        self.keymap.insert(action, buttons);
+        self.notify_change(action);
+        Ok(())
+    }
+
+    /// Add every button in `buttons` to an action's ButtonTuple, keeping the buttons which
+    /// are already bound to it and inserting the action if it is not already in this
+    /// InputRebind. If any of the new buttons can't be added, because they would not fit in
+    /// a single tuple, or would go over the limits set by `set_max_actions_per_button` or
+    /// `set_max_bindings`, the bindings are left unchanged and the error contains the number
+    /// of buttons which couldn't be added.
+    pub fn add_buttons(&mut self, action: A, buttons: ButtonTuple) -> std::result::Result<(), usize> {
+        let current = self.keymap.get(&action).cloned().unwrap_or_default();
+        let mut added = vec![];
+        let mut rejected = 0;
+        for b in buttons.buttons() {
+            if current.contains(b) || added.contains(&b) {
+                continue;
+            }
+            let fits = current.num_buttons_set() + added.len() < current.max_buttons();
+            let candidate = ButtonTuple::from_buttons(current.buttons().chain(added.iter().cloned()).chain(Some(b)));
+            if fits && self.check_limits(&[(action, Some(candidate))]).is_ok() {
+                added.push(b);
+            } else {
+                rejected += 1;
+            }
+        }
+
+        if rejected > 0 {
+            return Err(rejected);
+        }
+        self.keymap.insert(action, current.union(&buttons));
+        self.notify_change(action);
+        Ok(())
+    }
+
+    /// Move the buttons bound to `from` to `to`, removing `from` from this InputRebind. If
+    /// `to` already has buttons, the buttons of `from` are added after them, and any which
+    /// don't fit are discarded. The category of `from` is moved too, unless `to` already
+    /// has one. Returns false, doing nothing, if `from` is not in this InputRebind.
+    ///
+    /// Renaming never binds a button to more actions than before, nor adds to the number of
+    /// bindings, so it can't go over the limits set by `set_max_actions_per_button` and
+    /// `set_max_bindings`.
+    pub fn rename_action(&mut self, from: &A, to: A) -> bool {
+        if *from == to {
+            return self.keymap.contains_key(from);
+        }
+        let buttons = match self.keymap.remove(from) {
+            Some(buttons) => buttons,
+            None => return false,
+        };
+
+        let merged = self.keymap.get(&to).map_or(buttons, |existing| existing.union(&buttons));
+        self.keymap.insert(to, merged);
+        if let Some(category) = self.categories.remove(from) {
+            self.categories.entry(to).or_insert(category);
+        }
+        self.notify_change(to);
+        true
+    }
+
+    /// Set a listener which is called with an action and its new ButtonTuple whenever the
+    /// bindings of an action are changed by a method of this InputRebind, e.g. so that a
+    /// rebinding UI can redraw only the row which changed. Changes made through
+    /// `get_bindings_mut` are not reported. Replaces any previous listener.
+    pub fn set_change_listener(&mut self, listener: ChangeListener<A>) {
+        self.change_listener = ChangeListenerSlot(Some(listener));
+    }
+
+    /// Remove the change listener, if there is one.
+    pub fn remove_change_listener(&mut self) {
+        self.change_listener = ChangeListenerSlot(None);
+    }
+
+    /// Returns the maximum number of actions which a single button can be bound to.
+    pub fn get_max_actions_per_button(&self) -> Option<usize> {
+        self.max_actions_per_button
+    }
+
+    /// Set the maximum number of actions which a single button can be bound to, e.g.
+    /// `Some(1)` to forbid binding one key to several actions. Every method which adds
+    /// bindings fails rather than go over the limit, except `get_bindings_mut`, which isn't
+    /// checked. Bindings which already exceed the limit are kept. `None`, the default,
+    /// removes the limit.
+    pub fn set_max_actions_per_button(&mut self, max: Option<usize>) {
+        self.max_actions_per_button = max;
+    }
+
+    /// Returns the maximum number of buttons which can be bound across all actions.
+    pub fn get_max_bindings(&self) -> Option<usize> {
+        self.max_bindings
+    }
+
+    /// Set the maximum number of buttons which can be bound across all actions, e.g. to
+    /// limit the memory used by the keymap. Every method which adds bindings fails rather
+    /// than go over the limit, except `get_bindings_mut`, which isn't checked. Bindings
+    /// which already exceed the limit are kept. `None`, the default, removes the limit.
+    pub fn set_max_bindings(&mut self, max: Option<usize>) {
+        self.max_bindings = max;
+    }
+
+    /// Returns the number of buttons bound across all actions. A button bound to several
+    /// actions is counted once for each of them.
+    pub fn binding_count(&self) -> usize {
+        self.keymap.values().map(ButtonTuple::num_buttons_set).sum()
+    }
+
+    /// Returns the number of actions which this InputRebind can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.keymap.capacity()
+    }
+
+    /// Shrinks the memory used by this InputRebind as much as possible, e.g. after many
+    /// actions have been removed.
+    pub fn shrink_to_fit(&mut self) {
+        self.keymap.shrink_to_fit();
+        self.categories.shrink_to_fit();
+    }
+
+    /// Returns a copy of this InputRebind with each `(action, buttons)` override applied
+    /// as if by `insert_action_with_buttons`, failing if any of them would. This
+    /// InputRebind is left unchanged.
+    pub fn with_overrides(&self, overrides: &[(A, ButtonTuple)]) -> std::result::Result<Self, RebindError>
+        where S: Clone {
+        let mut rebind = self.clone();
+        for &(action, buttons) in overrides {
+            rebind.insert_action_with_buttons(action, buttons)?;
+        }
+        Ok(rebind)
+    }
+
+    /// Returns a copy of this InputRebind with the same bindings, but with the mouse settings
+    /// reset to their defaults. The viewport size is kept, since it describes the window
+    /// rather than a user preference.
+    pub fn clone_bindings_only(&self) -> Self
+        where S: Clone {
+        InputRebind {
+            keymap: self.keymap.clone(),
+            mouse_data: MouseTranslationData::new(self.mouse_data.viewport_size),
+            max_actions_per_button: self.max_actions_per_button,
+            max_bindings: self.max_bindings,
+            categories: self.categories.clone(),
+            change_listener: ChangeListenerSlot(None)
+        }
     }
 
     /// Return a reference to the current ButtonTuple stored for an action. If the action
@@ -375,6 +1949,253 @@ impl<A: Action, S: BuildHasher + Default> InputRebind<A, S> {
         self.keymap.get_mut(action)
     }
 
+    /// Check what would happen if `buttons` were assigned to `action` by
+    /// `insert_action_with_buttons`, without changing this InputRebind. The preview lists
+    /// the buttons which are already bound to other actions, the buttons which would be
+    /// bound to more actions than the limit set by `set_max_actions_per_button`, and how many
+    /// bindings would go over the limit set by `set_max_bindings`. As with
+    /// `insert_action_with_buttons`, the buttons replace the action's current ones.
+    pub fn preview_assign(&self, action: &A, buttons: &ButtonTuple) -> AssignPreview<A> {
+        let current = self.keymap.get(action).cloned().unwrap_or_default();
+        let before = self.binding_count();
+        let after = before - current.num_buttons_set() + buttons.num_buttons_set();
+
+        let mut preview = AssignPreview {
+            conflicts: vec![],
+            over_limit: vec![],
+            overflow: self.max_bindings.map_or(0, |max| after.saturating_sub(before.max(max)))
+        };
+
+        for b in buttons.buttons() {
+            let others = self.keymap
+                             .iter()
+                             .filter(|&(a, bt)| a != action && bt.contains(b))
+                             .map(|(&a, _)| a)
+                             .sorted();
+            if !current.contains(b) && self.max_actions_per_button.is_some_and(|max| others.len() + 1 > max) {
+                preview.over_limit.push(b);
+            }
+            if !others.is_empty() {
+                preview.conflicts.push((b, others));
+            }
+        }
+        preview
+    }
+
+    /// Returns true if this InputRebind binds exactly the same buttons to the same actions
+    /// as an `InputTranslator`. The mouse settings are not compared.
+    pub fn same_bindings_as(&self, translator: &InputTranslator<A, S>) -> bool {
+        translator.same_bindings_as(self)
+    }
+
+    /// Returns true if the button is bound to any action in this InputRebind.
+    pub fn is_button_bound(&self, button: &Button) -> bool {
+        self.keymap.values().any(|bt| bt.contains(*button))
+    }
+
+    /// Replaces every stored Action/ButtonTuple pair with those in `bindings`. The mouse
+    /// settings are left unchanged. Fails, leaving the bindings unchanged, if a button
+    /// would be bound to more actions than the limit set by `set_max_actions_per_button`,
+    /// or if the new bindings would add to the number of bindings past the limit set by
+    /// `set_max_bindings`.
+    pub fn set_bindings(&mut self, bindings: HashMap<A, ButtonTuple, S>) -> std::result::Result<(), RebindError> {
+        let changes = bindings.iter()
+                              .map(|(&a, &bt)| (a, Some(bt)))
+                              .chain(self.keymap.keys().filter(|a| !bindings.contains_key(a)).map(|&a| (a, None)))
+                              .collect_vec();
+        self.check_limits(&changes)?;
+        self.keymap = bindings;
+        for action in self.keymap.keys().cloned().collect_vec() {
+            self.notify_change(action);
+        }
+        Ok(())
+    }
+
+    /// Replace every occurrence of `old` with `new` in the ButtonTuples of all actions.
+    /// Returns the number of ButtonTuples which were modified. Fails, leaving the bindings
+    /// unchanged, if `new` would be bound to more actions than the limit set by
+    /// `set_max_actions_per_button`.
+    pub fn replace_button_everywhere(&mut self, old: Button, new: Button) -> std::result::Result<usize, RebindError> {
+        let replace = |slot: Option<Button>| if slot == Some(old) { Some(new) } else { slot };
+        let changes = self.keymap
+                          .iter()
+                          .filter(|&(_, bt)| bt.contains(old))
+                          .map(|(&a, bt)| (a, Some(ButtonTuple(replace(bt.0), replace(bt.1), replace(bt.2)))))
+                          .collect_vec();
+        self.check_limits(&changes)?;
+        for &(a, bt) in &changes {
+            if let Some(bt) = bt {
+                self.keymap.insert(a, bt);
+            }
+            self.notify_change(a);
+        }
+        Ok(changes.len())
+    }
+
+    /// Check that applying `changes`, each of which sets the ButtonTuple of an action or
+    /// removes the action for `None`, would not go over the limits set by
+    /// `set_max_actions_per_button` and `set_max_bindings`. Every method which adds
+    /// bindings goes through this. Only the bindings which a change adds are checked, so
+    /// that bindings which were over a limit before it was lowered are kept.
+    fn check_limits(&self, changes: &[(A, Option<ButtonTuple>)]) -> std::result::Result<(), RebindError> {
+        let mut after = self.keymap.iter().map(|(&a, &bt)| (a, bt)).collect::<HashMap<_, _>>();
+        for &(a, bt) in changes {
+            match bt {
+                Some(bt) => after.insert(a, bt),
+                None => after.remove(&a),
+            };
+        }
+
+        if let Some(max) = self.max_actions_per_button {
+            for &(a, bt) in changes {
+                for b in bt.iter().flat_map(ButtonTuple::buttons) {
+                    let added = !self.keymap.get(&a).is_some_and(|old| old.contains(b));
+                    if added && after.values().filter(|bt| bt.contains(b)).count() > max {
+                        return Err(RebindError::TooManyActions(b));
+                    }
+                }
+            }
+        }
+
+        if let Some(max) = self.max_bindings {
+            let total = after.values().map(ButtonTuple::num_buttons_set).sum::<usize>();
+            if total > max && total > self.binding_count() {
+                return Err(RebindError::TooManyBindings);
+            }
+        }
+        Ok(())
+    }
+
+    /// Make sure that each button is bound to at most one action, e.g. after merging two
+    /// sets of bindings. When a button is bound to several actions, the action which sorts
+    /// first according to `f` keeps it, and the button is removed from the ButtonTuples of
+    /// the others. Actions which compare equal are ordered by `Ord`.
+    pub fn resolve_conflicts_by<F: Fn(&A, &A) -> Ordering>(&mut self, f: F) {
+        let mut winners = HashMap::<Button, A>::new();
+        for (&a, bt) in self.keymap.iter() {
+            for b in bt.buttons() {
+                let winner = winners.entry(b).or_insert(a);
+                let wins = match f(&a, winner) {
+                    Ordering::Less => true,
+                    Ordering::Equal => a < *winner,
+                    Ordering::Greater => false,
+                };
+                if wins {
+                    *winner = a;
+                }
+            }
+        }
+
+        let mut modified = vec![];
+        for (&a, bt) in self.keymap.iter_mut() {
+            let old = *bt;
+            let remove = |slot: &mut Option<Button>| {
+                if slot.is_some_and(|b| winners[&b] != a) {
+                    *slot = None;
+                }
+            };
+            remove(&mut bt.0);
+            remove(&mut bt.1);
+            remove(&mut bt.2);
+            if *bt != old {
+                modified.push(a);
+            }
+        }
+        for a in modified {
+            self.notify_change(a);
+        }
+    }
+
+    /// Returns the category of an action, if one has been set.
+    pub fn get_category(&self, action: &A) -> Option<&str> {
+        self.categories.get(action).map(|c| &**c)
+    }
+
+    /// Set the category of an action, such as "Movement" or "Combat", which is used to
+    /// group actions into sections in a rebinding UI.
+    pub fn set_category(&mut self, action: A, category: &str) {
+        self.categories.insert(action, category.to_string());
+    }
+
+    /// Returns the actions in this InputRebind grouped by category, with the actions in
+    /// each category sorted. Actions without a category are grouped under the empty
+    /// string, which sorts before every other category.
+    pub fn actions_by_category(&self) -> BTreeMap<String, Vec<A>> {
+        let mut groups = BTreeMap::new();
+        for a in self.actions() {
+            let category = self.get_category(&a).unwrap_or("").to_string();
+            groups.entry(category).or_insert_with(Vec::new).push(a);
+        }
+        groups
+    }
+
+    /// Find groups of actions which are bound to exactly the same buttons, which usually
+    /// indicates a mistake in the configuration. The slots which the buttons are in are
+    /// ignored, and actions with no buttons are not reported. Each group is sorted, and
+    /// the groups are sorted by their first action.
+    pub fn duplicate_bindings(&self) -> Vec<Vec<A>> {
+        let mut groups: Vec<(ButtonTuple, Vec<A>)> = vec![];
+        for a in self.actions() {
+            let bt = self.keymap[&a];
+            if bt.num_buttons_set() == 0 {
+                continue;
+            }
+
+            match groups.iter_mut().find(|&&mut (ref group_bt, _)| group_bt.same_buttons(&bt)) {
+                Some(&mut (_, ref mut group)) => group.push(a),
+                None => groups.push((bt, vec![a])),
+            }
+        }
+
+        groups.into_iter().map(|(_, group)| group).filter(|group| group.len() > 1).collect()
+    }
+
+    /// Returns every bound button paired with its action, without building an
+    /// `InputTranslator`. The pairs are sorted by action, and then by slot.
+    pub fn button_action_pairs(&self) -> Vec<(Button, A)> {
+        self.actions()
+            .into_iter()
+            .flat_map(|a| self.keymap[&a].buttons().map(move |b| (b, a)))
+            .collect()
+    }
+
+    /// Returns the actions whose buttons belong to more than one `Device`, e.g. an action
+    /// bound to both a keyboard key and a controller button. Buttons on different
+    /// controllers count as different devices. The actions are sorted.
+    pub fn mixed_device_actions(&self) -> Vec<A> {
+        self.actions()
+            .into_iter()
+            .filter(|a| {
+                let mut devices = self.keymap[a].buttons().map(|b| Device::from_button(&b));
+                match devices.next() {
+                    Some(first) => devices.any(|d| d != first),
+                    None => false,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the actions, sorted, which have at least one button bound but can't be
+    /// triggered with only the `available` devices, e.g. to warn a player without a
+    /// controller that an action needs a keyboard binding. Actions with no buttons bound
+    /// are not included.
+    pub fn unreachable_actions(&self, available: &[Device]) -> Vec<A> {
+        self.actions()
+            .into_iter()
+            .filter(|a| {
+                let bt = &self.keymap[a];
+                bt.num_buttons_set() > 0 &&
+                bt.buttons().all(|b| !available.contains(&Device::from_button(&b)))
+            })
+            .collect()
+    }
+
+    /// Returns all of the actions stored in this InputRebind, sorted using their `Ord`
+    /// implementation.
+    pub fn actions(&self) -> Vec<A> {
+        self.keymap.keys().cloned().sorted()
+    }
+
     /// Returns whether mouse scroll along the x axis is inverted.
     pub fn get_x_scroll_inverted(&self) -> bool {
         self.mouse_data.x_axis_scroll_inverted
@@ -400,29 +2221,121 @@ impl<A: Action, S: BuildHasher + Default> InputRebind<A, S> {
         self.mouse_data.x_axis_motion_inverted
     }
 
-    /// Set whether mouse motion along the x axis is inverted.
+    /// Set whether mouse motion along the x axis is inverted. An inverted cursor position
+    /// `x` is translated to `width - x`, so the edges of the viewport (`0` and `width`) are
+    /// swapped and the centre is unchanged.
     pub fn set_x_motion_inverted(&mut self, invert_x_motion: bool) {
         self.mouse_data.x_axis_motion_inverted = invert_x_motion;
     }
 
-    /// Returns whether mouse scroll along the y axis is inverted.
+    /// Returns whether mouse motion along the y axis is inverted.
     pub fn get_y_motion_inverted(&self) -> bool {
         self.mouse_data.y_axis_motion_inverted
     }
 
-    /// Set whether mouse scroll along the y axis is inverted.
+    /// Set whether mouse motion along the y axis is inverted. An inverted cursor position
+    /// `y` is translated to `height - y`, so the edges of the viewport (`0` and `height`)
+    /// are swapped and the centre is unchanged.
     pub fn set_y_motion_inverted(&mut self, invert_y_motion: bool) {
         self.mouse_data.y_axis_motion_inverted = invert_y_motion;
     }
 
+    /// Returns the mouse sensitivity.
+    pub fn get_mouse_sensitivity(&self) -> f64 {
+        self.mouse_data.sensitivity
+    }
+
+    /// Set the mouse sensitivity, which scales every relative mouse motion before it is
+    /// clamped to the maximum delta. The default is 1.
+    pub fn set_mouse_sensitivity(&mut self, sensitivity: f64) {
+        self.mouse_data.sensitivity = sensitivity;
+    }
+
+    /// Returns the maximum magnitude of a single relative mouse motion, or `None` if
+    /// relative motion is not clamped.
+    pub fn get_max_mouse_delta(&self) -> Option<f64> {
+        self.mouse_data.max_delta
+    }
+
+    /// Set the maximum magnitude of a single relative mouse motion, after scaling by the
+    /// sensitivity. Larger motions are scaled down to this length, keeping their direction.
+    /// This also applies to scroll translated to motion and to motion locked to the centre.
+    /// Pass `None` to disable clamping.
+    pub fn set_max_mouse_delta(&mut self, max_delta: Option<f64>) {
+        self.mouse_data.max_delta = max_delta;
+    }
+
+    /// Returns the number of physical pixels per logical unit.
+    pub fn get_dpi_scale(&self) -> f64 {
+        self.mouse_data.dpi_scale
+    }
+
+    /// Set the number of physical pixels per logical unit, e.g. `2.0` on a HiDPI display.
+    /// Translated cursor positions are divided by this scale, so that they are in logical
+    /// units when the cursor position and viewport size are in physical pixels.
+    pub fn set_dpi_scale(&mut self, dpi_scale: f64) {
+        self.mouse_data.dpi_scale = dpi_scale;
+    }
+
+    /// Returns whether translated cursor positions are normalized.
+    pub fn get_normalize_cursor(&self) -> bool {
+        self.mouse_data.normalize_cursor
+    }
+
+    /// Set whether translated cursor positions are normalized to the range `[0, 1]` on each
+    /// axis, by dividing them by the viewport size. Inversion is applied first, and the DPI
+    /// scale is not applied to normalized positions.
+    pub fn set_normalize_cursor(&mut self, normalize: bool) {
+        self.mouse_data.normalize_cursor = normalize;
+    }
+
+    /// Returns whether cursor positions are translated into motion relative to the centre
+    /// of the viewport.
+    pub fn get_lock_to_center(&self) -> bool {
+        self.mouse_data.lock_to_center
+    }
+
+    /// Set whether cursor positions are translated into relative mouse motion from the
+    /// centre of the viewport, e.g. for a first person camera which warps the cursor back
+    /// to the centre every frame. Inversion is applied first, and the position is neither
+    /// normalized nor scaled.
+    pub fn set_lock_to_center(&mut self, lock: bool) {
+        self.mouse_data.lock_to_center = lock;
+    }
+
     /// Returns the viewport size used for calculating mouse positions.
     pub fn get_viewport_size(&self) -> Size {
         self.mouse_data.viewport_size
     }
 
     /// Set the viewport size used for calculating mouse positions.
-    pub fn set_viewport_size(&mut self, viewport_size: Size) {
-        self.mouse_data.viewport_size = viewport_size;
+    pub fn set_viewport_size<Sz: Into<ViewportSize>>(&mut self, viewport_size: Sz) {
+        self.mouse_data.viewport_size = Size::from(viewport_size.into());
+    }
+
+    /// Returns the motion which an `InputTranslator` with this InputRebind's mouse settings
+    /// would translate `motion` into, e.g. for previewing the settings on a calibration
+    /// screen.
+    pub fn preview_motion(&self, motion: Motion) -> Motion {
+        MouseTranslator {
+            data: self.mouse_data.clone(),
+            unrecognized_motions: 0
+        }
+        .translate(motion)
+    }
+
+    /// Restore every mouse setting, such as inversion and sensitivity, to its default value.
+    /// The viewport size and the bindings are not changed.
+    pub fn reset_mouse(&mut self) {
+        self.mouse_data = MouseTranslationData::new(self.mouse_data.viewport_size);
+    }
+
+    fn notify_change(&mut self, action: A) {
+        if let Some(ref mut listener) = self.change_listener.0 {
+            if let Some(bt) = self.keymap.get(&action) {
+                listener(&action, bt);
+            }
+        }
     }
 
     /// Convert the `InputRebind` into an `InputTranslator`. Consumes the
@@ -430,43 +2343,252 @@ impl<A: Action, S: BuildHasher + Default> InputRebind<A, S> {
     pub fn into_translator(self) -> InputTranslator<A, S> {
         self.into()
     }
+
+    /// Update an existing `InputTranslator` in place so that it has the bindings and mouse
+    /// settings of this `InputRebind`. The translator's keymap is cleared and refilled, so
+    /// its allocation is reused, which makes this cheaper than `into_translator` when
+    /// rebinding frequently. The translator's other settings and held buttons are kept.
+    pub fn update_translator(&self, translator: &mut InputTranslator<A, S>) {
+        translator.mouse_translator.data = self.mouse_data.clone();
+        translator.keymap.clear();
+        translator.keymap.reserve(self.keymap.values().map(ButtonTuple::num_buttons_set).sum());
+        for (&a, bt) in self.keymap.iter() {
+            for b in bt.buttons() {
+                translator.keymap.insert(b, a);
+            }
+        }
+    }
 }
 
-impl<A: Action, S: BuildHasher + Default> Into<InputTranslator<A, S>> for InputRebind<A, S> {
-    fn into(self) -> InputTranslator<A, S> {
-        let mut input_translator = InputTranslator::new(self.mouse_data.viewport_size);
-        input_translator.mouse_translator.data = self.mouse_data;
-        let key_vec = self.keymap
-                          .values()
-                          .flat_map(|bt| bt.into_iter().filter_map(|x| x))
-                          .collect_vec();
+impl<A: Action + Display, S: BuildHasher + Default> InputRebind<A, S> {
+    /// Returns the bindings as a table of strings, e.g. for printing a list of controls.
+    /// The first row is a header, followed by one row per action, sorted by action. Each
+    /// row contains the action's name followed by the names of the buttons in its three
+    /// slots, where empty slots are empty strings.
+    pub fn to_table(&self) -> Vec<Vec<String>> {
+        let header = vec!["Action".to_string(),
+                          "Button 1".to_string(),
+                          "Button 2".to_string(),
+                          "Button 3".to_string()];
+
+        let rows = self.actions().into_iter().map(|a| {
+            let mut row = vec![a.to_string()];
+            row.extend(self.keymap[&a].iter().map(|b| b.as_ref().map(button_display_name).unwrap_or_default()));
+            row
+        });
+
+        Some(header).into_iter().chain(rows).collect()
+    }
+
+    /// Returns a deterministic, line-based description of the bindings and mouse settings,
+    /// e.g. for comparing a control scheme against a golden file in a test. Actions are
+    /// sorted, and each action's buttons are written with `button_to_string`, sorted by
+    /// `OrderedButton` so that the slots which they are in don't matter.
+    pub fn to_sorted_string(&self) -> String {
+        let mut out = String::from("[bindings]\n");
+        for a in self.actions() {
+            let buttons = self.keymap[&a]
+                              .buttons()
+                              .map(OrderedButton)
+                              .sorted()
+                              .into_iter()
+                              .map(|b| button_to_string(&b.0))
+                              .join(", ");
+            out.push_str(&format!("{} = {}\n", a, buttons));
+        }
+
+        let m = &self.mouse_data;
+        out.push_str("[mouse]\n");
+        out.push_str(&format!("x_motion_inverted = {}\n", m.x_axis_motion_inverted));
+        out.push_str(&format!("y_motion_inverted = {}\n", m.y_axis_motion_inverted));
+        out.push_str(&format!("x_scroll_inverted = {}\n", m.x_axis_scroll_inverted));
+        out.push_str(&format!("y_scroll_inverted = {}\n", m.y_axis_scroll_inverted));
+        out.push_str(&format!("sensitivity = {:?}\n", m.sensitivity));
+        out.push_str(&format!("max_delta = {:?}\n", m.max_delta));
+        out.push_str(&format!("scroll_as_motion = {}\n", m.scroll_as_motion));
+        out.push_str(&format!("scroll_motion_factor = {:?}\n", m.scroll_motion_factor));
+        out.push_str(&format!("strict_motion = {}\n", m.strict_motion));
+        out.push_str(&format!("dpi_scale = {:?}\n", m.dpi_scale));
+        out.push_str(&format!("normalize_cursor = {}\n", m.normalize_cursor));
+        out.push_str(&format!("lock_to_center = {}\n", m.lock_to_center));
+        out.push_str(&format!("viewport_size = {}x{}\n", m.viewport_size.width, m.viewport_size.height));
+        out
+    }
+}
+
+impl<A: StableAction, S: BuildHasher + Default> InputRebind<A, S> {
+    /// Returns a fingerprint of the bindings in this InputRebind, which is useful to
+    /// identify a control scheme without sending the full configuration. The fingerprint
+    /// only depends on which buttons are bound to which actions, so it doesn't depend on
+    /// insertion order or slot positions, and the mouse settings are not included.
+    ///
+    /// Each action is identified by its `stable_id`, and each button by its key code, mouse
+    /// button number, or controller id and button number, so the fingerprint of a set of
+    /// bindings never changes, even if the actions are renamed or reordered.
+    pub fn fingerprint(&self) -> u64 {
+        let bindings = self.keymap
+                           .iter()
+                           .flat_map(|(a, bt)| bt.buttons().map(move |b| (a.stable_id(), button_code(&b))))
+                           .sorted();
+        let mut bytes = vec![];
+        for (id, (device, code, button)) in bindings {
+            bytes.extend_from_slice(&id.to_le_bytes());
+            bytes.push(device);
+            bytes.extend_from_slice(&code.to_le_bytes());
+            bytes.extend_from_slice(&button.to_le_bytes());
+        }
+        fnv1a(&bytes)
+    }
+}
+
+impl<A: Action + Decodable, S: BuildHasher + Default> InputRebind<A, S> {
+    /// Decode an InputRebind from JSON in the format written by its `Encodable` impl,
+    /// skipping any bindings for actions which can't be decoded, e.g. because they were
+    /// saved by a newer version of a game with more actions. Returns the decoded
+    /// InputRebind, along with the identifiers of the skipped actions. Fails if the JSON
+    /// is malformed in any other way.
+    pub fn from_json_lenient(s: &str) -> std::result::Result<(Self, Vec<String>), json::DecoderError> {
+        InputRebind::from_json_with_aliases(s, &AliasTable::new())
+    }
+
+    /// Decode an InputRebind from JSON like `from_json_lenient`, additionally resolving any
+    /// action identifier which can't be decoded through `aliases`. Only identifiers which
+    /// are neither valid actions nor aliases are skipped.
+    pub fn from_json_with_aliases(s: &str,
+                                  aliases: &AliasTable<A>)
+                                  -> std::result::Result<(Self, Vec<String>), json::DecoderError> {
+        let expected = |what: &str, found: &Json| json::DecoderError::ExpectedError(what.into(), found.to_string());
 
-        input_translator.keymap.reserve(key_vec.len());
-        for &k in &key_vec {
-            for (&a, bt) in self.keymap.iter() {
-                if bt.contains(k) {
-                    input_translator.keymap.insert(k, a);
+        let json = Json::from_str(s).map_err(json::DecoderError::ParseError)?;
+        let mut object = match json {
+            Json::Object(object) => object,
+            other => return Err(expected("Object", &other)),
+        };
+        let mouse_data = match object.remove("mouse_data") {
+            Some(mouse_data) => MouseTranslationData::decode(&mut json::Decoder::new(mouse_data))?,
+            None => return Err(json::DecoderError::MissingFieldError("mouse_data".into())),
+        };
+        let bindings = match object.remove("keymap") {
+            Some(Json::Array(bindings)) => bindings,
+            Some(other) => return Err(expected("Array", &other)),
+            None => return Err(json::DecoderError::MissingFieldError("keymap".into())),
+        };
+
+        let mut rebind = InputRebind::new(mouse_data.viewport_size);
+        rebind.mouse_data = mouse_data;
+        let mut skipped = vec![];
+        for binding in bindings {
+            let (action, buttons) = match binding {
+                Json::Array(ref pair) if pair.len() == 2 => (pair[0].clone(), pair[1].clone()),
+                other => return Err(expected("Array", &other)),
+            };
+            let buttons = ButtonTuple::decode(&mut json::Decoder::new(buttons))?;
+            let alias = match action {
+                Json::String(ref name) => aliases.resolve(name),
+                _ => None,
+            };
+            match A::decode(&mut json::Decoder::new(action.clone())).ok().or(alias) {
+                Some(action) => {
+                    rebind.keymap.insert(action, buttons);
+                }
+                None => {
+                    skipped.push(match action {
+                        Json::String(name) => name,
+                        other => other.to_string(),
+                    })
                 }
             }
         }
 
+        Ok((rebind, skipped))
+    }
+}
+
+/// The bindings are serialized as a list of `(action, buttons)` pairs sorted by action,
+/// followed by the mouse settings. The change listener, the limits on actions per button
+/// and on total bindings, and the action categories are not serialized.
+impl<A: Action + Encodable, S: BuildHasher> Encodable for InputRebind<A, S> {
+    fn encode<E: Encoder>(&self, e: &mut E) -> std::result::Result<(), E::Error> {
+        let bindings = self.keymap.iter().map(|(&a, &bt)| (a, bt)).sorted_by(|&(a0, _), &(a1, _)| a0.cmp(&a1));
+
+        e.emit_struct("InputRebind", 2, |e| {
+            e.emit_struct_field("keymap", 0, |e| bindings.encode(e))?;
+            e.emit_struct_field("mouse_data", 1, |e| self.mouse_data.encode(e))
+        })
+    }
+}
+
+impl<A: Action + Decodable, S: BuildHasher + Default> Decodable for InputRebind<A, S> {
+    fn decode<D: Decoder>(d: &mut D) -> std::result::Result<Self, D::Error> {
+        d.read_struct("InputRebind", 2, |d| {
+            let bindings: Vec<(A, ButtonTuple)> = d.read_struct_field("keymap", 0, Decodable::decode)?;
+            let mouse_data: MouseTranslationData = d.read_struct_field("mouse_data", 1, Decodable::decode)?;
+
+            let mut rebind = InputRebind::new(mouse_data.viewport_size);
+            rebind.keymap = bindings.into_iter().collect();
+            rebind.mouse_data = mouse_data;
+            Ok(rebind)
+        })
+    }
+}
+
+impl<A: Action, S: BuildHasher + Default> From<InputRebind<A, S>> for InputTranslator<A, S> {
+    fn from(rebind: InputRebind<A, S>) -> InputTranslator<A, S> {
+        let mut input_translator = InputTranslator::new(rebind.mouse_data.viewport_size);
+        rebind.update_translator(&mut input_translator);
         input_translator
     }
 }
 
-impl<A: Action, S: BuildHasher + Default> Into<InputRebind<A, S>> for InputTranslator<A, S> {
-    fn into(self) -> InputRebind<A, S> {
-        let mut input_rebind = InputRebind::new(self.mouse_translator.data.viewport_size);
+impl<A: Action, S: BuildHasher + Default> From<InputTranslator<A, S>> for InputRebind<A, S> {
+    fn from(translator: InputTranslator<A, S>) -> InputRebind<A, S> {
+        let mut input_rebind = InputRebind::new(translator.mouse_translator.data.viewport_size);
 
-        input_rebind.mouse_data = self.mouse_translator.data;
-        input_rebind.keymap = to_act_bt_hashmap(self.keymap.iter().map(|(b, a)| (*b, *a)));
+        input_rebind.mouse_data = translator.mouse_translator.data;
+        input_rebind.keymap = to_act_bt_hashmap(translator.keymap.iter().map(|(b, a)| (*b, *a)));
 
         input_rebind
     }
 }
 
+/// Utility function to split a duration into whole seconds and nanoseconds, which can be
+/// serialized.
+fn duration_to_parts(duration: Duration) -> (u64, u32) {
+    (duration.as_secs(), duration.subsec_nanos())
+}
+
+/// Utility function to convert tap/hold mappings into a serializable form, with each
+/// threshold split by `duration_to_parts`.
+fn tap_holds_to_parts<A: Action>(mappings: &[(Button, A, A, Duration)]) -> Vec<(Button, A, A, (u64, u32))> {
+    mappings.iter().map(|&(b, tap, hold, threshold)| (b, tap, hold, duration_to_parts(threshold))).collect()
+}
+
+/// Utility function to convert tap/hold mappings back from the form created by
+/// `tap_holds_to_parts`.
+fn tap_holds_from_parts<A: Action>(mappings: Vec<(Button, A, A, (u64, u32))>) -> Vec<(Button, A, A, Duration)> {
+    mappings.into_iter().map(|(b, tap, hold, (secs, nanos))| (b, tap, hold, Duration::new(secs, nanos))).collect()
+}
+
+/// Utility function to identify a button by its device, followed by its key code, mouse
+/// button number, or controller id and button number.
+fn button_code(button: &Button) -> (u8, u32, u32) {
+    match *button {
+        Button::Keyboard(key) => (0, key as u32, 0),
+        Button::Mouse(mouse_button) => (1, u32::from(mouse_button), 0),
+        Button::Controller(controller_button) => (2, controller_button.id, u32::from(controller_button.button)),
+    }
+}
+
+/// Utility function to hash bytes with 64 bit FNV-1a, whose output is fixed by its
+/// specification, unlike the hashers in the standard library.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
 /// Utility function to convert an iterator of (Button, Action) to a
-/// `HashMap<Action, ButtonTuple>`.
+/// `HashMap<Action, ButtonTuple>`. The sort is stable, so the buttons for each action
+/// are placed into the `ButtonTuple` in the order in which `iter` yields them. If a pair
+/// is yielded more than once, only its first occurrence is kept.
 fn to_act_bt_hashmap<I, A, S>(iter: I) -> HashMap<A, ButtonTuple, S>
     where I: Iterator<Item = (Button, A)>,
           A: Action,
@@ -477,7 +2599,9 @@ fn to_act_bt_hashmap<I, A, S>(iter: I) -> HashMap<A, ButtonTuple, S>
         .coalesce(|(a0, b0), (a1, b1)| {
             if a0 == a1 { Ok((a0, b0.into_iter().chain(b1).collect())) } else { Err(((a0, b0), (a1, b1))) }
         })
-        .map(|(a, bs)| {
+        .map(|(a, mut bs)| {
+            let mut seen = vec![];
+            bs.retain(|b| if seen.contains(b) { false } else { seen.push(*b); true });
             let buttons = &bs.iter()
                              .cloned()
                              .pad_using(3, |_| None)
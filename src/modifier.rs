@@ -0,0 +1,99 @@
+use input::{Button, Key};
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
+use std::ops::BitOr;
+
+/// A set of modifier keys, such as Shift and Ctrl. The left and right versions of each
+/// modifier key are treated as the same modifier.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ModifierMask(u8);
+
+impl ModifierMask {
+    /// No modifier keys.
+    pub const NONE: ModifierMask = ModifierMask(0);
+
+    /// Either Shift key.
+    pub const SHIFT: ModifierMask = ModifierMask(1);
+
+    /// Either Ctrl key.
+    pub const CTRL: ModifierMask = ModifierMask(1 << 1);
+
+    /// Either Alt key.
+    pub const ALT: ModifierMask = ModifierMask(1 << 2);
+
+    /// Returns true if every modifier in `other` is also in this mask.
+    pub fn contains(&self, other: ModifierMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if this mask contains no modifiers.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the modifier which a button represents, or `ModifierMask::NONE` if the
+    /// button is not a modifier key.
+    pub fn from_button(button: &Button) -> ModifierMask {
+        match *button {
+            Button::Keyboard(Key::LShift) | Button::Keyboard(Key::RShift) => ModifierMask::SHIFT,
+            Button::Keyboard(Key::LCtrl) | Button::Keyboard(Key::RCtrl) => ModifierMask::CTRL,
+            Button::Keyboard(Key::LAlt) | Button::Keyboard(Key::RAlt) => ModifierMask::ALT,
+            _ => ModifierMask::NONE,
+        }
+    }
+
+    /// Returns the modifiers which are represented by a set of held buttons.
+    pub fn from_held<'a, I: IntoIterator<Item = &'a Button>>(held: I) -> ModifierMask {
+        held.into_iter().fold(ModifierMask::NONE, |mask, b| mask | ModifierMask::from_button(b))
+    }
+}
+
+impl BitOr for ModifierMask {
+    type Output = ModifierMask;
+
+    fn bitor(self, other: ModifierMask) -> ModifierMask {
+        ModifierMask(self.0 | other.0)
+    }
+}
+
+impl Encodable for ModifierMask {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        self.0.encode(e)
+    }
+}
+
+impl Decodable for ModifierMask {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        Ok(ModifierMask(Decodable::decode(d)?))
+    }
+}
+
+/// A button which must be pressed while exactly a set of modifier keys are held, e.g.
+/// Shift+A.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ModifiedButton {
+    /// The button which is pressed.
+    pub button: Button,
+
+    /// The modifier keys which must be held when the button is pressed.
+    pub mods: ModifierMask
+}
+
+impl Encodable for ModifiedButton {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        e.emit_struct("ModifiedButton", 2, |e| {
+            e.emit_struct_field("button", 0, |e| self.button.encode(e))?;
+            e.emit_struct_field("mods", 1, |e| self.mods.encode(e))
+        })
+    }
+}
+
+impl Decodable for ModifiedButton {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("ModifiedButton", 2, |d| {
+            Ok(ModifiedButton {
+                button: d.read_struct_field("button", 0, Decodable::decode)?,
+                mods: d.read_struct_field("mods", 1, Decodable::decode)?
+            })
+        })
+    }
+}
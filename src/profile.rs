@@ -0,0 +1,66 @@
+use {Action, InputRebind, MouseTranslationData};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+/// A snapshot of the mouse settings of an `InputRebind`, such as inversion, sensitivity
+/// and clamping. The viewport size is not part of the mouse settings, since it depends
+/// on the window rather than on the user's preferences.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MouseSettings(MouseTranslationData);
+
+impl MouseSettings {
+    /// Copies the mouse settings of an `InputRebind`.
+    pub fn from_rebind<A: Action, S: BuildHasher + Default>(rebind: &InputRebind<A, S>) -> Self {
+        MouseSettings(rebind.mouse_data.clone())
+    }
+
+    /// Applies these mouse settings to an `InputRebind`, keeping its viewport size and
+    /// bindings.
+    pub fn apply_to<A: Action, S: BuildHasher + Default>(&self, rebind: &mut InputRebind<A, S>) {
+        let viewport_size = rebind.mouse_data.viewport_size;
+        rebind.mouse_data = self.0.clone();
+        rebind.mouse_data.viewport_size = viewport_size;
+    }
+}
+
+/// Stores a set of `MouseSettings` for each save slot, e.g. for games where each save has
+/// its own mouse preferences but the key bindings are shared.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MouseProfileStore {
+    slots: HashMap<u32, MouseSettings>
+}
+
+impl MouseProfileStore {
+    /// Creates a store with no saved slots.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Saves the mouse settings of an `InputRebind` under a slot, replacing any settings
+    /// which were already saved there.
+    pub fn save<A: Action, S: BuildHasher + Default>(&mut self, slot: u32, rebind: &InputRebind<A, S>) {
+        self.slots.insert(slot, MouseSettings::from_rebind(rebind));
+    }
+
+    /// Applies the mouse settings saved under a slot to an `InputRebind`. Returns false,
+    /// leaving the `InputRebind` unchanged, if nothing is saved under the slot.
+    pub fn load<A: Action, S: BuildHasher + Default>(&self, slot: u32, rebind: &mut InputRebind<A, S>) -> bool {
+        match self.slots.get(&slot) {
+            Some(settings) => {
+                settings.apply_to(rebind);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the mouse settings saved under a slot.
+    pub fn get(&self, slot: u32) -> Option<&MouseSettings> {
+        self.slots.get(&slot)
+    }
+
+    /// Removes the mouse settings saved under a slot, returning them.
+    pub fn remove(&mut self, slot: u32) -> Option<MouseSettings> {
+        self.slots.remove(&slot)
+    }
+}
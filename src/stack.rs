@@ -0,0 +1,75 @@
+use {Action, InputTranslator, Translated};
+use input::Input;
+use std::collections::hash_map::RandomState;
+use std::default::Default;
+use std::hash::BuildHasher;
+
+/// Determines whether events which are not translated by a context in an `InputStack`
+/// fall through to the context below it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ContextMode {
+    /// Events which are not translated by the context are discarded.
+    Blocking,
+
+    /// Events which are not translated by the context are passed on to the context below.
+    Transparent
+}
+
+/// A stack of `InputTranslator`s for layered input, e.g. gameplay, then a pause menu on
+/// top of it, then a confirmation dialog on top of that. Input is translated by the top
+/// context first, and falls through to lower contexts until it is translated or it reaches
+/// a blocking context.
+#[derive(Clone, Debug)]
+pub struct InputStack<A: Action, S: BuildHasher = RandomState> {
+    contexts: Vec<(InputTranslator<A, S>, ContextMode)>
+}
+
+impl<A: Action, S: BuildHasher + Default> InputStack<A, S> {
+    /// Creates an empty `InputStack`.
+    pub fn new() -> Self {
+        InputStack { contexts: vec![] }
+    }
+
+    /// Push a context onto the top of the stack.
+    pub fn push(&mut self, translator: InputTranslator<A, S>, mode: ContextMode) {
+        self.contexts.push((translator, mode));
+    }
+
+    /// Remove the top context from the stack and return its translator, or `None` if the
+    /// stack is empty.
+    pub fn pop(&mut self) -> Option<InputTranslator<A, S>> {
+        self.contexts.pop().map(|(translator, _)| translator)
+    }
+
+    /// Returns the number of contexts on the stack.
+    pub fn len(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Returns true if there are no contexts on the stack.
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+
+    /// Translate an Input using the contexts on the stack, starting from the top. If a
+    /// context does not translate the input, it is passed on to the next context down,
+    /// unless that context is `ContextMode::Blocking`.
+    pub fn translate(&mut self, input: &Input) -> Option<Translated<A>> {
+        for &mut (ref mut translator, mode) in self.contexts.iter_mut().rev() {
+            if let Some(translated) = translator.translate(input) {
+                return Some(translated);
+            }
+
+            if mode == ContextMode::Blocking {
+                break;
+            }
+        }
+        None
+    }
+}
+
+impl<A: Action, S: BuildHasher + Default> Default for InputStack<A, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,70 @@
+use {Action, Translated, button_code};
+use input::{Button, Input};
+use std::cmp::Ordering;
+
+/// A `Button` with a total order, so that buttons can be sorted and searched. Keyboard
+/// buttons sort before mouse buttons, which sort before controller buttons. Within each
+/// device, buttons are ordered by their key code, mouse button number, or controller id
+/// and button number.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct OrderedButton(pub Button);
+
+impl OrderedButton {
+    fn sort_key(&self) -> (u8, u32, u32) {
+        button_code(&self.0)
+    }
+}
+
+impl Ord for OrderedButton {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for OrderedButton {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A translator for a fixed set of bindings stored in a static slice, which doesn't need
+/// to allocate. Buttons are looked up with a binary search, so the slice must be sorted
+/// by `OrderedButton`. Mouse motion is passed through unchanged, as it would be by an
+/// `InputTranslator` with the default mouse settings.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticTranslator<A: Action + 'static> {
+    bindings: &'static [(Button, A)]
+}
+
+impl<A: Action> StaticTranslator<A> {
+    /// Creates a `StaticTranslator` from a slice of bindings sorted by `OrderedButton`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the bindings are not sorted.
+    pub fn new(bindings: &'static [(Button, A)]) -> Self {
+        debug_assert!(bindings.windows(2).all(|w| OrderedButton(w[0].0) <= OrderedButton(w[1].0)),
+                      "StaticTranslator bindings must be sorted by OrderedButton");
+        StaticTranslator { bindings }
+    }
+
+    /// Translate an Input into a Translated<A> event. Returns `None` if there is no
+    /// action associated with the `Input` variant.
+    pub fn translate(&self, input: &Input) -> Option<Translated<A>> {
+        match *input {
+            Input::Press(button) => self.get_action(button).map(Translated::Press),
+            Input::Release(button) => self.get_action(button).map(Translated::Release),
+            Input::Move(motion) => Some(Translated::Move(motion)),
+            _ => None,
+        }
+    }
+
+    /// Returns the action which a button is bound to.
+    pub fn get_action(&self, button: Button) -> Option<A> {
+        let key = OrderedButton(button);
+        self.bindings
+            .binary_search_by(|&(b, _)| OrderedButton(b).cmp(&key))
+            .ok()
+            .map(|i| self.bindings[i].1)
+    }
+}
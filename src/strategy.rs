@@ -0,0 +1,50 @@
+//! Strategies for generating arbitrary bindings with `proptest`, so that downstream crates
+//! can property test their input handling. Only available with the `proptest` feature.
+
+use {Action, Builder, ButtonTuple, InputRebind};
+use input::{Button, ControllerButton, Key, MouseButton};
+use proptest::prelude::*;
+use std::fmt::Debug;
+
+/// Returns a strategy which generates letter keys, mouse buttons and controller buttons.
+pub fn button() -> BoxedStrategy<Button> {
+    prop_oneof![(0x61u32..0x7B).prop_map(|code| Button::Keyboard(Key::from(code))),
+                (0u32..9).prop_map(|n| Button::Mouse(MouseButton::from(n))),
+                (0u32..4, 0u8..16).prop_map(|(id, button)| {
+                    Button::Controller(ControllerButton { id, button })
+                })]
+        .boxed()
+}
+
+/// Returns a strategy which generates a `ButtonTuple` with any combination of empty and
+/// occupied slots.
+pub fn button_tuple() -> BoxedStrategy<ButtonTuple> {
+    (prop::option::of(button()), prop::option::of(button()), prop::option::of(button()))
+        .prop_map(|(b0, b1, b2)| ButtonTuple(b0, b1, b2))
+        .boxed()
+}
+
+/// Returns a strategy which generates an `InputRebind` with up to 16 actions from
+/// `actions`, each bound to an arbitrary `ButtonTuple`. A button may be bound to several
+/// actions, or appear twice in one tuple.
+pub fn input_rebind<A, T>(actions: T) -> BoxedStrategy<InputRebind<A>>
+    where A: Action + Debug + 'static,
+          T: Strategy<Value = A> + 'static {
+    prop::collection::hash_map(actions, button_tuple(), 0..16)
+        .prop_map(|bindings| {
+            let mut rebind = InputRebind::new((800, 600));
+            rebind.set_bindings(bindings).expect("a new InputRebind has no limits");
+            rebind
+        })
+        .boxed()
+}
+
+/// Returns a strategy which generates an `InputRebind` in which every button is bound to
+/// at most one action, and appears at most once in its `ButtonTuple`.
+pub fn conflict_free_input_rebind<A, T>(actions: T) -> BoxedStrategy<InputRebind<A>>
+    where A: Action + Debug + 'static,
+          T: Strategy<Value = A> + 'static {
+    prop::collection::hash_map(button(), actions, 0..24)
+        .prop_map(|mappings| Builder::new((800, 600)).with_mappings(mappings).build_rebind())
+        .boxed()
+}
@@ -0,0 +1,80 @@
+use input::{Button, ControllerButton, Key, MouseButton};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result};
+
+/// An error which occurred while parsing a button from a string.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ParseError {
+    /// The device prefix before the first `:` was not recognised.
+    UnknownDevice(String),
+
+    /// The button was not recognised for its device.
+    UnknownButton(String)
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            ParseError::UnknownDevice(ref s) => write!(f, "unknown device in button string \"{}\"", s),
+            ParseError::UnknownButton(ref s) => write!(f, "unknown button in button string \"{}\"", s),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn description(&self) -> &str {
+        match *self {
+            ParseError::UnknownDevice(_) => "unknown device in button string",
+            ParseError::UnknownButton(_) => "unknown button in button string",
+        }
+    }
+}
+
+/// Convert a button into a compact string which can be stored in a text file, and read
+/// back using `button_from_string`. Keyboard keys are written as `"kb:W"`, mouse buttons
+/// as `"mouse:Left"`, and controller buttons as `"ctrl:<controller id>:<button>"`.
+pub fn button_to_string(button: &Button) -> String {
+    match *button {
+        Button::Keyboard(key) => format!("kb:{:?}", key),
+        Button::Mouse(mouse_button) => format!("mouse:{:?}", mouse_button),
+        Button::Controller(controller_button) => {
+            format!("ctrl:{}:{}", controller_button.id, controller_button.button)
+        }
+    }
+}
+
+/// Parse a button from a string in the format written by `button_to_string`.
+pub fn button_from_string(s: &str) -> ::std::result::Result<Button, ParseError> {
+    let unknown_button = || ParseError::UnknownButton(s.to_string());
+    let mut parts = s.splitn(2, ':');
+    let device = parts.next().unwrap_or("");
+    let name = parts.next().unwrap_or("");
+
+    match device {
+        "kb" => key_from_name(name).map(Button::Keyboard).ok_or_else(unknown_button),
+        "mouse" => mouse_button_from_name(name).map(Button::Mouse).ok_or_else(unknown_button),
+        "ctrl" => {
+            let mut parts = name.splitn(2, ':');
+            match (parts.next().map(str::parse), parts.next().map(str::parse)) {
+                (Some(Ok(id)), Some(Ok(button))) => {
+                    Ok(Button::Controller(ControllerButton { id, button }))
+                }
+                _ => Err(unknown_button()),
+            }
+        }
+        _ => Err(ParseError::UnknownDevice(s.to_string())),
+    }
+}
+
+fn key_from_name(name: &str) -> Option<Key> {
+    // Key codes follow SDL: printable keys use their ASCII codes, and all other keys are
+    // in a block starting at 0x40000039.
+    (0..0x80)
+        .chain(0x4000_0039..0x4000_011B)
+        .map(Key::from)
+        .find(|&key| key != Key::Unknown && format!("{:?}", key) == name)
+}
+
+fn mouse_button_from_name(name: &str) -> Option<MouseButton> {
+    (0..9).map(MouseButton::from).find(|&mouse_button| format!("{:?}", mouse_button) == name)
+}
@@ -0,0 +1,86 @@
+use {Action, ButtonTuple, InputRebind, MouseTranslationData};
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::ops::{Deref, DerefMut};
+
+/// A batch of changes to an `InputRebind`, e.g. for a settings dialog with "Apply" and
+/// "Cancel" buttons. The `InputRebind` is modified through the transaction, and its
+/// bindings, categories and mouse settings are restored when the transaction is dropped
+/// unless `commit` was called. Created by `InputRebind::begin_transaction`.
+#[derive(Debug)]
+pub struct Transaction<'a, A: Action + 'a, S: BuildHasher + Default + 'a = RandomState> {
+    rebind: &'a mut InputRebind<A, S>,
+    keymap: HashMap<A, ButtonTuple, S>,
+    mouse_data: MouseTranslationData,
+    max_actions_per_button: Option<usize>,
+    max_bindings: Option<usize>,
+    categories: HashMap<A, String, S>,
+    committed: bool
+}
+
+impl<A: Action, S: BuildHasher + Default + Clone> InputRebind<A, S> {
+    /// Start a batch of changes which are undone when the returned `Transaction` is dropped,
+    /// unless it is committed.
+    pub fn begin_transaction<'a>(&'a mut self) -> Transaction<'a, A, S> {
+        Transaction {
+            keymap: self.keymap.clone(),
+            mouse_data: self.mouse_data.clone(),
+            max_actions_per_button: self.max_actions_per_button,
+            max_bindings: self.max_bindings,
+            categories: self.categories.clone(),
+            rebind: self,
+            committed: false
+        }
+    }
+}
+
+impl<'a, A: Action, S: BuildHasher + Default> Transaction<'a, A, S> {
+    /// Keeps the changes made during the transaction.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+
+    /// Discards the changes made during the transaction. This is the same as dropping it.
+    pub fn rollback(self) {}
+}
+
+impl<'a, A: Action, S: BuildHasher + Default> Deref for Transaction<'a, A, S> {
+    type Target = InputRebind<A, S>;
+
+    fn deref(&self) -> &InputRebind<A, S> {
+        self.rebind
+    }
+}
+
+impl<'a, A: Action, S: BuildHasher + Default> DerefMut for Transaction<'a, A, S> {
+    fn deref_mut(&mut self) -> &mut InputRebind<A, S> {
+        self.rebind
+    }
+}
+
+/// Rolls back the changes unless the transaction was committed. The change listener is
+/// notified of every action whose bindings are restored.
+impl<'a, A: Action, S: BuildHasher + Default> Drop for Transaction<'a, A, S> {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+
+        let changed = self.keymap
+                          .iter()
+                          .filter(|&(a, bt)| self.rebind.keymap.get(a) != Some(bt))
+                          .map(|(&a, _)| a)
+                          .collect::<Vec<_>>();
+        self.rebind.keymap = HashMap::with_hasher(S::default());
+        self.rebind.keymap.extend(self.keymap.drain());
+        self.rebind.mouse_data = self.mouse_data.clone();
+        self.rebind.max_actions_per_button = self.max_actions_per_button;
+        self.rebind.max_bindings = self.max_bindings;
+        self.rebind.categories = HashMap::with_hasher(S::default());
+        self.rebind.categories.extend(self.categories.drain());
+        for a in changed {
+            self.rebind.notify_change(a);
+        }
+    }
+}
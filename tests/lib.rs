@@ -2,15 +2,20 @@
 
 extern crate rebind;
 extern crate input;
+#[cfg(feature = "proptest")]
+#[macro_use]
+extern crate proptest;
+extern crate rustc_serialize;
 extern crate window;
 
 use input::Input;
 use input::Button::Keyboard;
 use input::keyboard::Key;
-use rebind::{Action, Builder, ButtonTuple, InputRebind, InputTranslator, Translated};
+use rebind::{Action, Builder, ButtonTuple, InputRebind, InputTranslator, RebindError, StableAction, Translated};
+use std::fmt;
 use window::Size;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Ord, PartialOrd, RustcEncodable, RustcDecodable)]
 enum TestAction {
     Action1,
     Action2,
@@ -26,6 +31,26 @@ enum TestAction {
 
 impl Action for TestAction { }
 
+impl StableAction for TestAction {
+    fn stable_id(&self) -> u32 {
+        *self as u32
+    }
+
+    fn from_stable_id(id: u32) -> Option<Self> {
+        use TestAction::*;
+        [Action1, Action2, Action3, Action4, Action5, Action6, Action7, Action8, Action9, Action10]
+            .iter()
+            .find(|a| a.stable_id() == id)
+            .cloned()
+    }
+}
+
+impl fmt::Display for TestAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
 type TestBuilder = Builder<TestAction>;
 type TestTranslator = InputTranslator<TestAction>;
 type TestRebind = InputRebind<TestAction>;
@@ -51,7 +76,7 @@ fn populate_builder(builder: TestBuilder) -> TestBuilder {
 
 #[test]
 fn test_translator_get_action_from_buttonpress() {
-    let translator = create_prepopulated_builder().build_translator();
+    let mut translator = create_prepopulated_builder().build_translator();
 
     assert_eq!(translator.translate(&Input::Press(Keyboard(Key::Down))).unwrap(),
                Translated::Press(TestAction::Action2));
@@ -88,9 +113,9 @@ fn test_add_button_to_translator_using_rebind() {
     let translator = create_prepopulated_builder().build_translator();
     let mut rebind = translator.into_rebind();
     rebind.insert_action_with_buttons(TestAction::Action5,
-                                      ButtonTuple(Some(Q_KEY), Some(E_KEY), None));
+                                      ButtonTuple(Some(Q_KEY), Some(E_KEY), None)).unwrap();
 
-    let translator = rebind.into_translator();
+    let mut translator = rebind.into_translator();
 
     assert_eq!(translator.translate(&Input::Press(Q_KEY)),
                Some(Translated::Press(TestAction::Action5)));
@@ -103,7 +128,7 @@ const TEST_SIZE: Size = Size { width: 800, height: 600 };
 #[test]
 fn test_unmodified_mouse_input_works() {
     use input::Motion;
-    let translator = create_prepopulated_builder_with_size(TEST_SIZE).build_translator();
+    let mut translator = create_prepopulated_builder_with_size(TEST_SIZE).build_translator();
     let mouse_motion = Input::Move(Motion::MouseCursor(45.0, 11.0));
     assert_eq!(translator.translate(&mouse_motion),
                Some(Translated::Move(Motion::MouseCursor(45.0, 11.0))));
@@ -112,7 +137,7 @@ fn test_unmodified_mouse_input_works() {
 #[test]
 fn test_mirror_mouse_input_along_x_axis() {
     use input::Motion;
-    let translator = create_prepopulated_builder_with_size(TEST_SIZE)
+    let mut translator = create_prepopulated_builder_with_size(TEST_SIZE)
                          .x_motion_inverted(true)
                          .build_translator();
     let mouse_motion = Input::Move(Motion::MouseCursor(45.0, 11.0));
@@ -123,7 +148,7 @@ fn test_mirror_mouse_input_along_x_axis() {
 #[test]
 fn test_mirror_mouse_input_along_y_axis() {
     use input::Motion;
-    let translator = create_prepopulated_builder_with_size(TEST_SIZE)
+    let mut translator = create_prepopulated_builder_with_size(TEST_SIZE)
                          .y_motion_inverted(true)
                          .build_translator();
     let mouse_motion = Input::Move(Motion::MouseCursor(45.0, 11.0));
@@ -160,3 +185,1852 @@ fn test_get_button_iter_len() {
     let _ = bti.next();
     assert_eq!(bti.len(), 0);
 }
+
+#[test]
+fn test_build_rebind_preserves_insertion_order() {
+    let rebind = TestBuilder::default()
+                     .with_mapping(TestAction::Action1, Keyboard(Key::W))
+                     .with_mapping(TestAction::Action1, Keyboard(Key::Up))
+                     .build_rebind();
+
+    let bindings = rebind.get_bindings(&TestAction::Action1).unwrap();
+    assert_eq!(bindings.0, Some(Keyboard(Key::W)));
+    assert_eq!(bindings.1, Some(Keyboard(Key::Up)));
+    assert_eq!(bindings.2, None);
+}
+
+#[test]
+fn test_rebind_actions_are_sorted() {
+    let mut rebind = TestRebind::new(TEST_SIZE);
+    rebind.insert_action(TestAction::Action7);
+    rebind.insert_action(TestAction::Action2);
+    rebind.insert_action(TestAction::Action10);
+    rebind.insert_action(TestAction::Action1);
+
+    assert_eq!(rebind.actions(),
+               vec![TestAction::Action1, TestAction::Action2, TestAction::Action7, TestAction::Action10]);
+}
+
+#[test]
+fn test_clamp_relative_mouse_motion() {
+    use input::Motion;
+    let huge_motion = Input::Move(Motion::MouseRelative(300.0, 400.0));
+
+    let mut translator = create_prepopulated_builder().max_mouse_delta(Some(10.0)).build_translator();
+    assert_eq!(translator.translate(&huge_motion),
+               Some(Translated::Move(Motion::MouseRelative(6.0, 8.0))));
+
+    let mut translator = create_prepopulated_builder().max_mouse_delta(None).build_translator();
+    assert_eq!(translator.translate(&huge_motion),
+               Some(Translated::Move(Motion::MouseRelative(300.0, 400.0))));
+
+    // The sensitivity is applied before clamping
+    let mut translator = create_prepopulated_builder()
+                             .mouse_sensitivity(2.0)
+                             .max_mouse_delta(Some(10.0))
+                             .build_translator();
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseRelative(3.0, 0.0))),
+               Some(Translated::Move(Motion::MouseRelative(6.0, 0.0))));
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseRelative(0.0, 6.0))),
+               Some(Translated::Move(Motion::MouseRelative(0.0, 10.0))));
+}
+
+#[test]
+fn test_clamp_scroll_and_centred_motion() {
+    use input::Motion;
+    let mut translator = create_prepopulated_builder()
+                             .scroll_as_motion(true)
+                             .scroll_motion_factor(100.0)
+                             .max_mouse_delta(Some(10.0))
+                             .build_translator();
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseScroll(0.0, 2.0))),
+               Some(Translated::Move(Motion::MouseRelative(0.0, 10.0))));
+
+    let mut translator = create_prepopulated_builder()
+                             .lock_to_center(true)
+                             .mouse_sensitivity(0.5)
+                             .max_mouse_delta(Some(10.0))
+                             .build_translator();
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseCursor(410.0, 290.0))),
+               Some(Translated::Move(Motion::MouseRelative(5.0, -5.0))));
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseCursor(800.0, 300.0))),
+               Some(Translated::Move(Motion::MouseRelative(10.0, 0.0))));
+}
+
+#[test]
+fn test_translator_actions_for_buttons() {
+    let translator = create_prepopulated_builder().build_translator();
+    let buttons = [Keyboard(Key::W), Keyboard(Key::Q), Keyboard(Key::Right), Keyboard(Key::Z)];
+
+    assert_eq!(translator.actions_for_buttons(&buttons),
+               vec![Some(TestAction::Action1), None, Some(TestAction::Action4), None]);
+    assert_eq!(translator.actions_for_buttons(&[]), vec![]);
+}
+
+#[test]
+fn test_translate_filtered() {
+    use std::collections::HashSet;
+    let mut translator = create_prepopulated_builder().build_translator();
+    let mut allowed = HashSet::new();
+    allowed.insert(TestAction::Action1);
+
+    assert_eq!(translator.translate_filtered(&Input::Press(Keyboard(Key::W)), &allowed),
+               Some(Translated::Press(TestAction::Action1)));
+    assert_eq!(translator.translate_filtered(&Input::Release(Keyboard(Key::W)), &allowed),
+               Some(Translated::Release(TestAction::Action1)));
+    assert_eq!(translator.translate_filtered(&Input::Press(Keyboard(Key::S)), &allowed), None);
+
+    allowed.clear();
+    allowed.insert(TestAction::Action2);
+    assert_eq!(translator.translate_filtered(&Input::Press(Keyboard(Key::W)), &allowed), None);
+    assert_eq!(translator.translate_filtered(&Input::Press(Keyboard(Key::S)), &allowed),
+               Some(Translated::Press(TestAction::Action2)));
+}
+
+#[test]
+fn test_disable_controller_translation() {
+    use input::{Button, ControllerButton};
+    let controller_button = Button::Controller(ControllerButton { id: 0, button: 5 });
+    let mut translator = create_prepopulated_builder()
+                             .with_mapping(TestAction::Action5, controller_button)
+                             .build_translator();
+
+    assert!(translator.get_controller_enabled());
+    assert_eq!(translator.translate(&Input::Press(controller_button)),
+               Some(Translated::Press(TestAction::Action5)));
+
+    translator.set_controller_enabled(false);
+    assert_eq!(translator.translate(&Input::Press(controller_button)), None);
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::W))),
+               Some(Translated::Press(TestAction::Action1)));
+
+    translator.set_keyboard_enabled(false);
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::W))), None);
+}
+
+#[test]
+fn test_button_tuple_union() {
+    let a = ButtonTuple(Some(Keyboard(Key::A)), None, None);
+    let b = ButtonTuple(Some(Keyboard(Key::B)), Some(Keyboard(Key::A)), None);
+    assert_eq!(a.union(&b),
+               ButtonTuple(Some(Keyboard(Key::A)), Some(Keyboard(Key::B)), None));
+
+    // Only the first three distinct buttons fit, the rest are truncated.
+    let c = ButtonTuple(Some(Keyboard(Key::A)), Some(Keyboard(Key::B)), None);
+    let d = ButtonTuple(Some(Keyboard(Key::C)), Some(Keyboard(Key::D)), None);
+    assert_eq!(c.union(&d),
+               ButtonTuple(Some(Keyboard(Key::A)), Some(Keyboard(Key::B)), Some(Keyboard(Key::C))));
+}
+
+#[test]
+fn test_button_tuple_intersection_and_difference() {
+    let a = ButtonTuple(Some(Keyboard(Key::A)), Some(Keyboard(Key::B)), Some(Keyboard(Key::C)));
+    let b = ButtonTuple(Some(Keyboard(Key::C)), None, Some(Keyboard(Key::A)));
+
+    assert_eq!(a.intersection(&b),
+               ButtonTuple(Some(Keyboard(Key::A)), Some(Keyboard(Key::C)), None));
+    assert_eq!(a.difference(&b), ButtonTuple(Some(Keyboard(Key::B)), None, None));
+    assert_eq!(b.difference(&a), ButtonTuple::new());
+}
+
+#[test]
+fn test_translate_iter_is_lazy() {
+    use std::cell::Cell;
+    let mut translator = create_prepopulated_builder().build_translator();
+    let inputs = vec![Input::Press(Keyboard(Key::W)),
+                      Input::Press(Keyboard(Key::Q)),
+                      Input::Press(Keyboard(Key::S)),
+                      Input::Press(Keyboard(Key::D)),
+                      Input::Press(Keyboard(Key::A))];
+    let consumed = Cell::new(0);
+
+    let translated = translator.translate_iter(inputs.into_iter().inspect(|_| consumed.set(consumed.get() + 1)))
+                               .take(2)
+                               .collect::<Vec<_>>();
+
+    assert_eq!(translated,
+               vec![Translated::Press(TestAction::Action1), Translated::Press(TestAction::Action2)]);
+    assert_eq!(consumed.get(), 3);
+}
+
+#[test]
+fn test_inverted_mouse_cursor_at_edges_and_centre() {
+    use input::Motion;
+    let mut translator = create_prepopulated_builder_with_size(TEST_SIZE)
+                         .x_motion_inverted(true)
+                         .y_motion_inverted(true)
+                         .build_translator();
+    let mut translate_cursor = |x, y| translator.translate(&Input::Move(Motion::MouseCursor(x, y)));
+
+    assert_eq!(translate_cursor(0.0, 0.0),
+               Some(Translated::Move(Motion::MouseCursor(800.0, 600.0))));
+    assert_eq!(translate_cursor(400.0, 300.0),
+               Some(Translated::Move(Motion::MouseCursor(400.0, 300.0))));
+    assert_eq!(translate_cursor(800.0, 600.0),
+               Some(Translated::Move(Motion::MouseCursor(0.0, 0.0))));
+}
+
+#[test]
+fn test_any_button_action() {
+    let mut translator = create_prepopulated_builder().build_translator();
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::Q))), None);
+
+    translator.set_any_button_action(Some(TestAction::Action9));
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::Q))),
+               Some(Translated::Press(TestAction::Action9)));
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::W))),
+               Some(Translated::Press(TestAction::Action1)));
+
+    translator.set_any_button_action(None);
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::Q))), None);
+}
+
+#[test]
+fn test_replace_button_everywhere() {
+    let old = Keyboard(Key::Space);
+    let new = Keyboard(Key::Return);
+    let mut rebind = TestRebind::new(TEST_SIZE);
+    rebind.insert_action_with_buttons(TestAction::Action1, ButtonTuple(Some(old), None, None)).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action2,
+                                      ButtonTuple(Some(Keyboard(Key::A)), Some(old), None)).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action3, ButtonTuple(Some(Keyboard(Key::B)), None, None)).unwrap();
+
+    assert_eq!(rebind.replace_button_everywhere(old, new).unwrap(), 2);
+    assert_eq!(rebind.get_bindings(&TestAction::Action1),
+               Some(&ButtonTuple(Some(new), None, None)));
+    assert_eq!(rebind.get_bindings(&TestAction::Action2),
+               Some(&ButtonTuple(Some(Keyboard(Key::A)), Some(new), None)));
+    assert_eq!(rebind.get_bindings(&TestAction::Action3),
+               Some(&ButtonTuple(Some(Keyboard(Key::B)), None, None)));
+    assert_eq!(rebind.replace_button_everywhere(old, new).unwrap(), 0);
+}
+
+#[test]
+fn test_viewport_size_conversions() {
+    use rebind::ViewportSize;
+    assert_eq!(ViewportSize::from((1024, 768)), ViewportSize(1024, 768));
+    assert_eq!(ViewportSize::from(Size { width: 1024, height: 768 }), ViewportSize(1024, 768));
+
+    let builder = TestBuilder::default().viewport_size((1024, 768));
+    assert_eq!(builder.get_viewport_size(), Size { width: 1024, height: 768 });
+
+    let mut rebind = builder.build_rebind();
+    rebind.set_viewport_size(Size { width: 640, height: 480 });
+    assert_eq!(rebind.get_viewport_size(), Size { width: 640, height: 480 });
+    rebind.set_viewport_size(ViewportSize(320, 240));
+    assert_eq!(rebind.get_viewport_size(), Size { width: 320, height: 240 });
+}
+
+#[test]
+fn test_translated_display() {
+    use input::Motion;
+    assert_eq!(Translated::Press(TestAction::Action1).to_string(), "Press(Action1)");
+    assert_eq!(Translated::Release(TestAction::Action2).to_string(), "Release(Action2)");
+    assert_eq!(Translated::Move::<TestAction>(Motion::MouseCursor(45.5, 11.0)).to_string(),
+               "Move(MouseCursor(45.5, 11))");
+}
+
+#[test]
+fn test_pause_and_resume_translation() {
+    use input::Motion;
+    let mut translator = create_prepopulated_builder().build_translator();
+    let press = Input::Press(Keyboard(Key::W));
+    let motion = Input::Move(Motion::MouseCursor(45.0, 11.0));
+
+    assert_eq!(translator.translate(&press), Some(Translated::Press(TestAction::Action1)));
+
+    translator.set_paused(true);
+    assert!(translator.get_paused());
+    assert_eq!(translator.translate(&press), None);
+    assert_eq!(translator.translate(&motion), None);
+
+    translator.set_paused(false);
+    assert_eq!(translator.translate(&press), Some(Translated::Press(TestAction::Action1)));
+    assert_eq!(translator.translate(&motion),
+               Some(Translated::Move(Motion::MouseCursor(45.0, 11.0))));
+}
+
+#[test]
+fn test_rebind_fingerprint() {
+    let mut rebind1 = TestRebind::new(TEST_SIZE);
+    rebind1.insert_action_with_buttons(TestAction::Action1,
+                                       ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::Up)), None)).unwrap();
+    rebind1.insert_action_with_buttons(TestAction::Action2, ButtonTuple(Some(Keyboard(Key::S)), None, None)).unwrap();
+
+    let mut rebind2 = TestRebind::new(TEST_SIZE);
+    rebind2.insert_action_with_buttons(TestAction::Action2, ButtonTuple(None, Some(Keyboard(Key::S)), None)).unwrap();
+    rebind2.insert_action_with_buttons(TestAction::Action1,
+                                       ButtonTuple(Some(Keyboard(Key::Up)), Some(Keyboard(Key::W)), None)).unwrap();
+    assert_eq!(rebind1.fingerprint(), rebind2.fingerprint());
+    // The fingerprint must not change between releases
+    assert_eq!(rebind1.fingerprint(), 0xcae2_d649_77cf_7e22);
+
+    let mut rebind3 = TestRebind::new(TEST_SIZE);
+    rebind3.insert_action_with_buttons(TestAction::Action1,
+                                       ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::S)), None)).unwrap();
+    rebind3.insert_action_with_buttons(TestAction::Action2, ButtonTuple(Some(Keyboard(Key::Up)), None, None)).unwrap();
+    assert!(rebind1.fingerprint() != rebind3.fingerprint());
+
+    // The same button bound to a different action is a different control scheme
+    let mut rebind4 = TestRebind::new(TEST_SIZE);
+    rebind4.insert_action_with_buttons(TestAction::Action1,
+                                       ButtonTuple(Some(Keyboard(Key::Space)), None, None)).unwrap();
+    let mut rebind5 = TestRebind::new(TEST_SIZE);
+    rebind5.insert_action_with_buttons(TestAction::Action2,
+                                       ButtonTuple(Some(Keyboard(Key::Space)), None, None)).unwrap();
+    assert!(rebind4.fingerprint() != rebind5.fingerprint());
+}
+
+#[test]
+fn test_axis_from_opposing_actions() {
+    const AXIS: u32 = 0;
+    let mut translator = create_prepopulated_builder().build_translator();
+    translator.bind_axis(AXIS, TestAction::Action3, TestAction::Action4);
+
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::D))),
+               Some(Translated::Axis(AXIS, 1.0)));
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::A))),
+               Some(Translated::Axis(AXIS, 0.0)));
+    assert_eq!(translator.translate(&Input::Release(Keyboard(Key::A))),
+               Some(Translated::Axis(AXIS, 1.0)));
+    assert_eq!(translator.translate(&Input::Release(Keyboard(Key::D))),
+               Some(Translated::Axis(AXIS, 0.0)));
+
+    // Actions which aren't part of an axis are unaffected.
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::W))),
+               Some(Translated::Press(TestAction::Action1)));
+}
+
+#[test]
+fn test_clear_translator_bindings() {
+    use input::Motion;
+    let mut translator = create_prepopulated_builder_with_size(TEST_SIZE)
+                             .x_motion_inverted(true)
+                             .build_translator();
+    translator.clear();
+
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::W))), None);
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::D))), None);
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseCursor(45.0, 11.0))),
+               Some(Translated::Move(Motion::MouseCursor(755.0, 11.0))));
+}
+
+#[test]
+fn test_rebind_to_table() {
+    use input::{Button, MouseButton};
+    let mut rebind = TestRebind::new(TEST_SIZE);
+    let left_click = Button::Mouse(MouseButton::Left);
+    rebind.insert_action_with_buttons(TestAction::Action2,
+                                      ButtonTuple(Some(Keyboard(Key::S)), Some(left_click), None)).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action1, ButtonTuple(Some(Keyboard(Key::W)), None, None)).unwrap();
+
+    assert_eq!(rebind.to_table(),
+               vec![vec!["Action", "Button 1", "Button 2", "Button 3"],
+                    vec!["Action1", "W", "", ""],
+                    vec!["Action2", "S", "Mouse Left", ""]]);
+}
+
+#[test]
+fn test_invert_relative_mouse_motion() {
+    use input::Motion;
+    let relative_motion = Input::Move(Motion::MouseRelative(5.0, -3.0));
+
+    let mut translator = create_prepopulated_builder().build_translator();
+    assert_eq!(translator.translate(&relative_motion),
+               Some(Translated::Move(Motion::MouseRelative(5.0, -3.0))));
+
+    let mut translator = create_prepopulated_builder().x_motion_inverted(true).build_translator();
+    assert_eq!(translator.translate(&relative_motion),
+               Some(Translated::Move(Motion::MouseRelative(-5.0, -3.0))));
+
+    let mut translator = create_prepopulated_builder().y_motion_inverted(true).build_translator();
+    assert_eq!(translator.translate(&relative_motion),
+               Some(Translated::Move(Motion::MouseRelative(5.0, 3.0))));
+}
+
+#[test]
+fn test_builder_serialization_round_trip() {
+    use rustc_serialize::json;
+    use rebind::{ModifierMask, ScrollDir};
+    use std::time::Duration;
+    let builder = create_prepopulated_builder()
+                      .with_mapping(TestAction::Action5, Keyboard(Key::Space))
+                      .with_modified_mapping(TestAction::Action6, Keyboard(Key::S), ModifierMask::CTRL)
+                      .with_tap_hold_mapping(Keyboard(Key::R),
+                                             TestAction::Action7,
+                                             TestAction::Action8,
+                                             Duration::from_millis(300))
+                      .with_scroll_mapping(ScrollDir::Up, TestAction::Action9, true)
+                      .x_motion_inverted(true)
+                      .max_mouse_delta(Some(20.0));
+
+    let encoded = json::encode(&builder).unwrap();
+    let decoded: TestBuilder = json::decode(&encoded).unwrap();
+
+    assert!(decoded.get_x_motion_inverted());
+    assert_eq!(decoded.get_max_mouse_delta(), Some(20.0));
+    assert_eq!(json::encode(&decoded).unwrap(), encoded);
+    assert!(decoded.build_translator() == builder.build_translator());
+}
+
+#[test]
+fn test_insert_at_first_free() {
+    let mut bt = ButtonTuple::new();
+    assert_eq!(bt.insert_at_first_free(Keyboard(Key::A)), Some(0));
+    assert_eq!(bt.insert_at_first_free(Keyboard(Key::B)), Some(1));
+    assert_eq!(bt.insert_at_first_free(Keyboard(Key::C)), Some(2));
+    assert_eq!(bt.insert_at_first_free(Keyboard(Key::D)), None);
+    assert_eq!(bt, ButtonTuple(Some(Keyboard(Key::A)), Some(Keyboard(Key::B)), Some(Keyboard(Key::C))));
+
+    bt.1 = None;
+    assert_eq!(bt.insert_at_first_free(Keyboard(Key::D)), Some(1));
+}
+
+#[test]
+fn test_input_stack_fall_through() {
+    use rebind::{ContextMode, InputStack};
+    let menu = || TestBuilder::default().with_mapping(TestAction::Action9, Keyboard(Key::Escape)).build_translator();
+    let mut stack = InputStack::new();
+    stack.push(create_prepopulated_builder().build_translator(), ContextMode::Blocking);
+
+    stack.push(menu(), ContextMode::Transparent);
+    assert_eq!(stack.translate(&Input::Press(Keyboard(Key::Escape))),
+               Some(Translated::Press(TestAction::Action9)));
+    assert_eq!(stack.translate(&Input::Press(Keyboard(Key::W))),
+               Some(Translated::Press(TestAction::Action1)));
+
+    assert!(stack.pop().is_some());
+    stack.push(menu(), ContextMode::Blocking);
+    assert_eq!(stack.translate(&Input::Press(Keyboard(Key::Escape))),
+               Some(Translated::Press(TestAction::Action9)));
+    assert_eq!(stack.translate(&Input::Press(Keyboard(Key::W))), None);
+
+    assert!(stack.pop().is_some());
+    assert_eq!(stack.len(), 1);
+    assert_eq!(stack.translate(&Input::Press(Keyboard(Key::W))),
+               Some(Translated::Press(TestAction::Action1)));
+}
+
+#[test]
+fn test_translator_serialization_skips_runtime_state() {
+    use rustc_serialize::json;
+    use std::time::Duration;
+    const AXIS: u32 = 0;
+    let mut translator = create_prepopulated_builder().y_motion_inverted(true).build_translator();
+    translator.bind_axis(AXIS, TestAction::Action3, TestAction::Action4);
+    translator.set_debounce(Duration::from_millis(15));
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::D))),
+               Some(Translated::Axis(AXIS, 1.0)));
+
+    let encoded = json::encode(&translator).unwrap();
+    let mut decoded: TestTranslator = json::decode(&encoded).unwrap();
+    assert_eq!(decoded.get_debounce(), Duration::from_millis(15));
+
+    // The original translator still has `D` held, but the decoded one starts fresh.
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::A))),
+               Some(Translated::Axis(AXIS, 0.0)));
+    assert_eq!(decoded.translate(&Input::Press(Keyboard(Key::A))),
+               Some(Translated::Axis(AXIS, -1.0)));
+    assert_eq!(decoded.translate(&Input::Press(Keyboard(Key::W))),
+               Some(Translated::Press(TestAction::Action1)));
+
+    let rebind = decoded.into_rebind();
+    assert!(rebind.get_y_motion_inverted());
+    assert!(!rebind.get_x_motion_inverted());
+}
+
+#[test]
+fn test_rebind_duplicate_bindings() {
+    let mut rebind = TestRebind::new(TEST_SIZE);
+    rebind.insert_action_with_buttons(TestAction::Action1,
+                                      ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::Up)), None)).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action2,
+                                      ButtonTuple(None, Some(Keyboard(Key::Up)), Some(Keyboard(Key::W)))).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action3,
+                                      ButtonTuple(Some(Keyboard(Key::W)), None, None)).unwrap();
+    rebind.insert_action(TestAction::Action4);
+    rebind.insert_action(TestAction::Action5);
+
+    assert_eq!(rebind.duplicate_bindings(), vec![vec![TestAction::Action1, TestAction::Action2]]);
+}
+
+#[test]
+fn test_button_string_round_trip() {
+    use input::{Button, ControllerButton, MouseButton};
+    use rebind::{ParseError, button_from_string, button_to_string};
+
+    let buttons = [(Keyboard(Key::W), "kb:W"),
+                   (Keyboard(Key::D1), "kb:D1"),
+                   (Keyboard(Key::Up), "kb:Up"),
+                   (Keyboard(Key::Space), "kb:Space"),
+                   (Button::Mouse(MouseButton::Left), "mouse:Left"),
+                   (Button::Mouse(MouseButton::X2), "mouse:X2"),
+                   (Button::Controller(ControllerButton { id: 0, button: 5 }), "ctrl:0:5")];
+
+    for &(button, s) in buttons.iter() {
+        assert_eq!(button_to_string(&button), s);
+        assert_eq!(button_from_string(s), Ok(button));
+    }
+
+    assert_eq!(button_from_string("kb:NotAKey"),
+               Err(ParseError::UnknownButton("kb:NotAKey".to_string())));
+    assert_eq!(button_from_string("ctrl:0"), Err(ParseError::UnknownButton("ctrl:0".to_string())));
+    assert_eq!(button_from_string("pad:A"), Err(ParseError::UnknownDevice("pad:A".to_string())));
+}
+
+#[test]
+fn test_builder_with_unbound_action() {
+    let rebind = create_prepopulated_builder()
+                     .with_action(TestAction::Action6)
+                     .with_action(TestAction::Action1)
+                     .build_rebind();
+
+    assert_eq!(rebind.get_bindings(&TestAction::Action6), Some(&ButtonTuple::new()));
+    assert_eq!(rebind.get_bindings(&TestAction::Action1),
+               Some(&ButtonTuple(Some(Keyboard(Key::Up)), Some(Keyboard(Key::W)), None)));
+    assert_eq!(rebind.get_bindings(&TestAction::Action7), None);
+}
+
+#[test]
+fn test_rebind_with_overrides() {
+    let base = create_prepopulated_builder().build_rebind();
+    let jump = ButtonTuple(Some(Keyboard(Key::Space)), None, None);
+    let down = ButtonTuple(Some(Keyboard(Key::LCtrl)), None, None);
+
+    let modded = base.with_overrides(&[(TestAction::Action5, jump), (TestAction::Action2, down)]).unwrap();
+
+    assert_eq!(modded.get_bindings(&TestAction::Action5), Some(&jump));
+    assert_eq!(modded.get_bindings(&TestAction::Action2), Some(&down));
+    assert_eq!(modded.get_bindings(&TestAction::Action1), base.get_bindings(&TestAction::Action1));
+
+    assert_eq!(base.get_bindings(&TestAction::Action5), None);
+    assert_eq!(base.get_bindings(&TestAction::Action2),
+               Some(&ButtonTuple(Some(Keyboard(Key::Down)), Some(Keyboard(Key::S)), None)));
+}
+
+#[test]
+fn test_custom_event_handler() {
+    use input::EventId;
+    use std::any::Any;
+    use std::sync::Arc;
+    const SHAKE: EventId = EventId("shake");
+    const OTHER: EventId = EventId("other");
+
+    let mut translator = create_prepopulated_builder().build_translator();
+    let shake = Input::Custom(SHAKE, Arc::new(2u32));
+    assert_eq!(translator.translate(&shake), None);
+
+    translator.set_custom_handler(SHAKE,
+                                  Box::new(|data: &Arc<dyn Any>| {
+                                      match data.downcast_ref::<u32>() {
+                                          Some(&2) => Some(Translated::Press(TestAction::Action8)),
+                                          _ => None,
+                                      }
+                                  }));
+
+    assert_eq!(translator.translate(&shake), Some(Translated::Press(TestAction::Action8)));
+    assert_eq!(translator.translate(&Input::Custom(SHAKE, Arc::new(3u32))), None);
+    assert_eq!(translator.translate(&Input::Custom(OTHER, Arc::new(2u32))), None);
+
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+    assert_send_sync(&translator);
+}
+
+#[test]
+fn test_active_bitset() {
+    let order = [TestAction::Action1, TestAction::Action2, TestAction::Action3, TestAction::Action4];
+    let mut translator = create_prepopulated_builder().build_translator();
+    assert_eq!(translator.active_bitset(&order), 0);
+
+    translator.translate(&Input::Press(Keyboard(Key::S)));
+    translator.translate(&Input::Press(Keyboard(Key::Right)));
+    assert_eq!(translator.active_bitset(&order), 0b1010);
+
+    translator.translate(&Input::Release(Keyboard(Key::S)));
+    assert_eq!(translator.active_bitset(&order), 0b1000);
+}
+
+#[test]
+fn test_rebind_button_action_pairs() {
+    let mut rebind = TestRebind::new(TEST_SIZE);
+    rebind.insert_action_with_buttons(TestAction::Action2, ButtonTuple(None, Some(Keyboard(Key::S)), None)).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action1,
+                                      ButtonTuple(Some(Keyboard(Key::W)), None, Some(Keyboard(Key::Up)))).unwrap();
+    rebind.insert_action(TestAction::Action3);
+
+    assert_eq!(rebind.button_action_pairs(),
+               vec![(Keyboard(Key::W), TestAction::Action1),
+                    (Keyboard(Key::Up), TestAction::Action1),
+                    (Keyboard(Key::S), TestAction::Action2)]);
+}
+
+#[test]
+fn test_rebind_mixed_device_actions() {
+    use input::{Button, ControllerButton};
+    let pad_a = Button::Controller(ControllerButton { id: 0, button: 0 });
+    let pad_b = Button::Controller(ControllerButton { id: 0, button: 1 });
+    let mut rebind = TestRebind::new(TEST_SIZE);
+    rebind.insert_action_with_buttons(TestAction::Action1,
+                                      ButtonTuple(Some(Keyboard(Key::Space)), None, Some(pad_a))).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action2, ButtonTuple(Some(pad_a), Some(pad_b), None)).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action3,
+                                      ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::Up)), None)).unwrap();
+    rebind.insert_action(TestAction::Action4);
+
+    assert_eq!(rebind.mixed_device_actions(), vec![TestAction::Action1]);
+}
+
+#[test]
+fn test_translator_last_device() {
+    use input::{Button, ControllerButton, Motion};
+    use rebind::Device;
+    let mut translator = create_prepopulated_builder().build_translator();
+    assert_eq!(translator.last_device(), None);
+
+    translator.translate(&Input::Press(Keyboard(Key::W)));
+    assert_eq!(translator.last_device(), Some(Device::Keyboard));
+
+    translator.translate(&Input::Press(Button::Controller(ControllerButton { id: 1, button: 3 })));
+    assert_eq!(translator.last_device(), Some(Device::Controller(1)));
+
+    translator.translate(&Input::Move(Motion::MouseRelative(1.0, 1.0)));
+    assert_eq!(translator.last_device(), Some(Device::Mouse));
+}
+
+#[test]
+fn test_button_tuple_index() {
+    let bt = ButtonTuple(Some(Keyboard(Key::A)), None, Some(Keyboard(Key::C)));
+    assert_eq!(bt[0], Some(Keyboard(Key::A)));
+    assert_eq!(bt[1], None);
+    assert_eq!(bt[2], Some(Keyboard(Key::C)));
+}
+
+#[test]
+#[should_panic]
+fn test_button_tuple_index_out_of_bounds() {
+    let bt = ButtonTuple::new();
+    let _ = bt[3];
+}
+
+#[test]
+fn test_rebind_set_bindings() {
+    use std::collections::HashMap;
+    let mut rebind: TestRebind = create_prepopulated_builder().x_scroll_inverted(true).build_rebind();
+
+    let mut bindings = HashMap::new();
+    bindings.insert(TestAction::Action5, ButtonTuple(Some(Keyboard(Key::Space)), None, None));
+    bindings.insert(TestAction::Action6, ButtonTuple(Some(Keyboard(Key::E)), Some(Keyboard(Key::Q)), None));
+    rebind.set_bindings(bindings).unwrap();
+
+    assert_eq!(rebind.get_bindings(&TestAction::Action1), None);
+    assert_eq!(rebind.get_bindings(&TestAction::Action5),
+               Some(&ButtonTuple(Some(Keyboard(Key::Space)), None, None)));
+    assert_eq!(rebind.get_bindings(&TestAction::Action6),
+               Some(&ButtonTuple(Some(Keyboard(Key::E)), Some(Keyboard(Key::Q)), None)));
+    assert!(rebind.get_x_scroll_inverted());
+    assert_eq!(rebind.get_viewport_size(), TEST_SIZE);
+}
+
+#[test]
+fn test_translator_release_on_last_button() {
+    let mut translator = create_prepopulated_builder().build_translator();
+    translator.set_release_on_last_button(true);
+
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::Up))),
+               Some(Translated::Press(TestAction::Action1)));
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::W))),
+               Some(Translated::Press(TestAction::Action1)));
+    assert_eq!(translator.translate(&Input::Release(Keyboard(Key::Up))), None);
+    assert_eq!(translator.translate(&Input::Release(Keyboard(Key::W))),
+               Some(Translated::Release(TestAction::Action1)));
+
+    translator.set_release_on_last_button(false);
+    translator.translate(&Input::Press(Keyboard(Key::Up)));
+    translator.translate(&Input::Press(Keyboard(Key::W)));
+    assert_eq!(translator.translate(&Input::Release(Keyboard(Key::Up))),
+               Some(Translated::Release(TestAction::Action1)));
+}
+
+#[test]
+fn test_scroll_as_motion() {
+    use input::Motion;
+    let scroll = Input::Move(Motion::MouseScroll(0.0, 2.0));
+
+    let mut translator = create_prepopulated_builder()
+                             .scroll_as_motion(true)
+                             .scroll_motion_factor(10.0)
+                             .build_translator();
+    assert_eq!(translator.translate(&scroll),
+               Some(Translated::Move(Motion::MouseRelative(0.0, 20.0))));
+
+    let mut translator = create_prepopulated_builder().scroll_motion_factor(10.0).build_translator();
+    assert_eq!(translator.translate(&scroll),
+               Some(Translated::Move(Motion::MouseScroll(0.0, 2.0))));
+}
+
+#[test]
+fn test_device_classification() {
+    use input::{Button, ControllerAxisArgs, ControllerButton, Motion, MouseButton};
+    use rebind::Device;
+    assert_eq!(Device::from_button(&Keyboard(Key::A)), Device::Keyboard);
+    assert_eq!(Device::from_button(&Button::Mouse(MouseButton::Left)), Device::Mouse);
+    assert_eq!(Device::from_button(&Button::Controller(ControllerButton { id: 2, button: 0 })),
+               Device::Controller(2));
+    assert_eq!(Device::from_motion(&Motion::MouseCursor(1.0, 1.0)), Device::Mouse);
+    assert_eq!(Device::from_motion(&Motion::MouseScroll(0.0, 1.0)), Device::Mouse);
+    assert_eq!(Device::from_motion(&Motion::ControllerAxis(ControllerAxisArgs { id: 3, axis: 0, position: 0.5 })),
+               Device::Controller(3));
+}
+
+#[test]
+fn test_translate_with_device() {
+    use input::{Button, EventId, MouseButton};
+    use rebind::Device;
+    use std::any::Any;
+    use std::sync::Arc;
+    const SHAKE: EventId = EventId("shake");
+    let mut translator = create_prepopulated_builder()
+                             .with_mapping(TestAction::Action5, Button::Mouse(MouseButton::Left))
+                             .build_translator();
+    translator.set_custom_handler(SHAKE, Box::new(|_: &Arc<dyn Any>| Some(Translated::Press(TestAction::Action8))));
+
+    assert_eq!(translator.translate_with_device(&Input::Press(Keyboard(Key::W))),
+               Some((Translated::Press(TestAction::Action1), Device::Keyboard)));
+    assert_eq!(translator.translate_with_device(&Input::Press(Button::Mouse(MouseButton::Left))),
+               Some((Translated::Press(TestAction::Action5), Device::Mouse)));
+    assert_eq!(translator.translate_with_device(&Input::Custom(SHAKE, Arc::new(()))),
+               Some((Translated::Press(TestAction::Action8), Device::Custom(SHAKE))));
+    assert_eq!(translator.translate_with_device(&Input::Press(Keyboard(Key::Z))), None);
+}
+
+#[test]
+fn test_rebind_max_actions_per_button() {
+    let mut rebind: TestRebind = create_prepopulated_builder().build_rebind();
+    assert_eq!(rebind.get_max_actions_per_button(), None);
+    assert_eq!(rebind.add_button(TestAction::Action5, Keyboard(Key::W)), Ok(()));
+
+    rebind.set_max_actions_per_button(Some(1));
+    assert_eq!(rebind.add_button(TestAction::Action6, Keyboard(Key::Up)),
+               Err(RebindError::TooManyActions(Keyboard(Key::Up))));
+    assert_eq!(rebind.get_bindings(&TestAction::Action6), None);
+
+    assert_eq!(rebind.add_button(TestAction::Action6, Keyboard(Key::E)), Ok(()));
+    assert_eq!(rebind.add_button(TestAction::Action1, Keyboard(Key::E)),
+               Err(RebindError::TooManyActions(Keyboard(Key::E))));
+    assert_eq!(rebind.get_bindings(&TestAction::Action6),
+               Some(&ButtonTuple(Some(Keyboard(Key::E)), None, None)));
+}
+
+#[test]
+fn test_rebind_max_actions_per_button_replace() {
+    use std::collections::HashMap;
+    let mut rebind: TestRebind = create_prepopulated_builder().build_rebind();
+    assert_eq!(rebind.add_button(TestAction::Action5, Keyboard(Key::W)), Ok(()));
+    rebind.set_max_actions_per_button(Some(1));
+
+    let down = ButtonTuple(Some(Keyboard(Key::E)), Some(Keyboard(Key::Down)), None);
+    assert_eq!(rebind.insert_action_with_buttons(TestAction::Action6, down),
+               Err(RebindError::TooManyActions(Keyboard(Key::Down))));
+    assert_eq!(rebind.get_bindings(&TestAction::Action6), None);
+    assert_eq!(rebind.with_overrides(&[(TestAction::Action6, down)]).err(),
+               Some(RebindError::TooManyActions(Keyboard(Key::Down))));
+
+    // W was bound to two actions before the limit was set, so it may stay bound to both
+    let kept = ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::Q)), None);
+    assert_eq!(rebind.insert_action_with_buttons(TestAction::Action5, kept),
+               Ok(Some(ButtonTuple(Some(Keyboard(Key::W)), None, None))));
+
+    assert_eq!(rebind.replace_button_everywhere(Keyboard(Key::Q), Keyboard(Key::Left)),
+               Err(RebindError::TooManyActions(Keyboard(Key::Left))));
+    assert_eq!(rebind.get_bindings(&TestAction::Action5), Some(&kept));
+    assert_eq!(rebind.replace_button_everywhere(Keyboard(Key::Q), Keyboard(Key::E)), Ok(1));
+
+    let mut bindings = HashMap::new();
+    bindings.insert(TestAction::Action1, ButtonTuple(Some(Keyboard(Key::Up)), None, None));
+    bindings.insert(TestAction::Action2, ButtonTuple(Some(Keyboard(Key::Up)), None, None));
+    assert_eq!(rebind.set_bindings(bindings.clone()), Err(RebindError::TooManyActions(Keyboard(Key::Up))));
+    assert_eq!(rebind.get_bindings(&TestAction::Action5),
+               Some(&ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::E)), None)));
+    bindings.remove(&TestAction::Action1);
+    assert_eq!(rebind.set_bindings(bindings), Ok(()));
+
+    assert!(rebind.rename_action(&TestAction::Action2, TestAction::Action7));
+    assert_eq!(rebind.get_bindings(&TestAction::Action7),
+               Some(&ButtonTuple(Some(Keyboard(Key::Up)), None, None)));
+}
+
+#[test]
+fn test_rebind_add_button_no_free_slot() {
+    let mut rebind: TestRebind = create_prepopulated_builder().build_rebind();
+    assert_eq!(rebind.add_button(TestAction::Action1, Keyboard(Key::I)), Ok(()));
+    assert_eq!(rebind.add_button(TestAction::Action1, Keyboard(Key::K)), Err(RebindError::NoFreeSlot));
+    assert_eq!(rebind.get_bindings(&TestAction::Action1),
+               Some(&ButtonTuple(Some(Keyboard(Key::Up)), Some(Keyboard(Key::W)), Some(Keyboard(Key::I)))));
+}
+
+#[test]
+fn test_rebind_update_translator() {
+    let mut translator = create_prepopulated_builder().build_translator();
+    let mut rebind = translator.clone().into_rebind();
+
+    rebind.insert_action_with_buttons(TestAction::Action1,
+                                      ButtonTuple(Some(Keyboard(Key::Space)), None, None)).unwrap();
+    rebind.update_translator(&mut translator);
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::Space))),
+               Some(Translated::Press(TestAction::Action1)));
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::W))), None);
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::S))),
+               Some(Translated::Press(TestAction::Action2)));
+
+    rebind.insert_action_with_buttons(TestAction::Action2,
+                                      ButtonTuple(Some(Keyboard(Key::Space)), None, None)).unwrap();
+    rebind.insert_action(TestAction::Action1);
+    rebind.set_x_scroll_inverted(true);
+    rebind.update_translator(&mut translator);
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::Space))),
+               Some(Translated::Press(TestAction::Action2)));
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::S))), None);
+    assert!(translator.into_rebind().get_x_scroll_inverted());
+}
+
+#[test]
+fn test_modified_mapping() {
+    use rebind::ModifierMask;
+    let mut translator = create_prepopulated_builder()
+                             .with_mapping(TestAction::Action5, Keyboard(Key::A))
+                             .with_modified_mapping(TestAction::Action6, Keyboard(Key::A), ModifierMask::SHIFT)
+                             .with_modified_mapping(TestAction::Action7,
+                                                    Keyboard(Key::A),
+                                                    ModifierMask::SHIFT | ModifierMask::CTRL)
+                             .build_translator();
+
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::A))),
+               Some(Translated::Press(TestAction::Action5)));
+    assert_eq!(translator.translate(&Input::Release(Keyboard(Key::A))),
+               Some(Translated::Release(TestAction::Action5)));
+
+    translator.translate(&Input::Press(Keyboard(Key::LShift)));
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::A))),
+               Some(Translated::Press(TestAction::Action6)));
+    // Releasing the modifier first still releases the modified action
+    translator.translate(&Input::Release(Keyboard(Key::LShift)));
+    assert_eq!(translator.translate(&Input::Release(Keyboard(Key::A))),
+               Some(Translated::Release(TestAction::Action6)));
+
+    translator.translate(&Input::Press(Keyboard(Key::RShift)));
+    translator.translate(&Input::Press(Keyboard(Key::LCtrl)));
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::A))),
+               Some(Translated::Press(TestAction::Action7)));
+}
+
+#[test]
+fn test_replay() {
+    let mut translator = create_prepopulated_builder().build_translator();
+    translator.set_release_on_last_button(true);
+
+    let script = [Input::Press(Keyboard(Key::W)),
+                  Input::Press(Keyboard(Key::Up)),
+                  Input::Press(Keyboard(Key::Z)),
+                  Input::Release(Keyboard(Key::W)),
+                  Input::Release(Keyboard(Key::Up))];
+    assert_eq!(rebind::replay(&mut translator, &script),
+               vec![Some(Translated::Press(TestAction::Action1)),
+                    Some(Translated::Press(TestAction::Action1)),
+                    None,
+                    None,
+                    Some(Translated::Release(TestAction::Action1))]);
+}
+
+#[test]
+fn test_strict_motion() {
+    use input::{ControllerAxisArgs, Motion};
+    let mut translator = create_prepopulated_builder().x_motion_inverted(true).build_translator();
+    translator.set_strict_motion(true);
+    assert!(translator.get_strict_motion());
+
+    let axis = Motion::ControllerAxis(ControllerAxisArgs { id: 0, axis: 1, position: 0.25 });
+    assert_eq!(translator.translate(&Input::Move(axis)), Some(Translated::Move(axis)));
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseRelative(3.0, 4.0))),
+               Some(Translated::Move(Motion::MouseRelative(-3.0, 4.0))));
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseScroll(0.0, 1.0))),
+               Some(Translated::Move(Motion::MouseScroll(0.0, 1.0))));
+    assert_eq!(translator.unrecognized_motion_count(), 0);
+
+    // Motion which can't be translated is passed through unchanged and counted
+    let broken = Motion::MouseCursor(f64::INFINITY, 4.0);
+    assert_eq!(translator.translate(&Input::Move(broken)), Some(Translated::Move(broken)));
+    let broken_axis = Motion::ControllerAxis(ControllerAxisArgs { id: 0, axis: 1, position: f64::NAN });
+    translator.translate(&Input::Move(broken_axis));
+    assert_eq!(translator.unrecognized_motion_count(), 2);
+
+    // Outside of strict mode nothing is counted
+    translator.set_strict_motion(false);
+    translator.translate(&Input::Move(broken));
+    assert_eq!(translator.unrecognized_motion_count(), 2);
+}
+
+#[test]
+fn test_rebind_clone_bindings_only() {
+    let rebind: TestRebind = create_prepopulated_builder()
+                                 .x_motion_inverted(true)
+                                 .y_scroll_inverted(true)
+                                 .max_mouse_delta(Some(10.0))
+                                 .build_rebind();
+    let clone = rebind.clone_bindings_only();
+
+    for action in rebind.actions() {
+        assert_eq!(clone.get_bindings(&action), rebind.get_bindings(&action));
+    }
+    assert_eq!(clone.actions(), rebind.actions());
+    assert!(!clone.get_x_motion_inverted());
+    assert!(!clone.get_y_scroll_inverted());
+    assert_eq!(clone.get_max_mouse_delta(), None);
+    assert_eq!(clone.get_viewport_size(), TEST_SIZE);
+}
+
+#[test]
+fn test_rebind_try_insert_action() {
+    let mut rebind: TestRebind = create_prepopulated_builder().build_rebind();
+    assert!(!rebind.try_insert_action(TestAction::Action1));
+    assert_eq!(rebind.get_bindings(&TestAction::Action1),
+               Some(&ButtonTuple(Some(Keyboard(Key::Up)), Some(Keyboard(Key::W)), None)));
+
+    assert!(rebind.try_insert_action(TestAction::Action5));
+    assert_eq!(rebind.get_bindings(&TestAction::Action5), Some(&ButtonTuple::new()));
+}
+
+#[test]
+fn test_translated_record_round_trip() {
+    use input::Motion;
+    use rebind::TranslatedRecord;
+    const ACTIONS: [TestAction; 3] = [TestAction::Action1, TestAction::Action2, TestAction::Action3];
+    let index_of = |a: &TestAction| ACTIONS.iter().position(|b| b == a).unwrap() as u32;
+    let action_of = |i: u32| ACTIONS.get(i as usize).cloned();
+
+    let events = vec![Translated::Press(TestAction::Action2),
+                      Translated::Move(Motion::MouseCursor(10.0, 20.0)),
+                      Translated::Release(TestAction::Action2),
+                      Translated::Axis(1, -1.0)];
+    let records = events.iter().map(|t| t.to_record(index_of)).collect::<Vec<_>>();
+    assert_eq!(records,
+               vec![TranslatedRecord::Press(1),
+                    TranslatedRecord::Move(Motion::MouseCursor(10.0, 20.0)),
+                    TranslatedRecord::Release(1),
+                    TranslatedRecord::Axis(1, -1.0)]);
+
+    let decoded = records.iter().map(|r| Translated::from_record(r, action_of).unwrap()).collect::<Vec<_>>();
+    assert_eq!(decoded, events);
+    assert_eq!(Translated::from_record(&TranslatedRecord::Press(7), action_of), None);
+}
+
+#[test]
+fn test_builder_with_mappings() {
+    let mappings = vec![(Keyboard(Key::I), TestAction::Action5),
+                        (Keyboard(Key::J), TestAction::Action6),
+                        (Keyboard(Key::K), TestAction::Action6)];
+    let mut translator: TestTranslator = Builder::new(TEST_SIZE).with_mappings(mappings.clone()).build_translator();
+
+    for &(button, action) in &mappings {
+        assert_eq!(translator.translate(&Input::Press(button)), Some(Translated::Press(action)));
+    }
+}
+
+#[test]
+fn test_rebind_resolve_conflicts_by() {
+    let mut rebind: TestRebind = create_prepopulated_builder().build_rebind();
+    let other: TestRebind = Builder::new(TEST_SIZE)
+                                .with_mapping(TestAction::Action5, Keyboard(Key::W))
+                                .with_mapping(TestAction::Action5, Keyboard(Key::Space))
+                                .with_mapping(TestAction::Action6, Keyboard(Key::S))
+                                .build_rebind();
+    for action in other.actions() {
+        rebind.insert_action_with_buttons(action, *other.get_bindings(&action).unwrap()).unwrap();
+    }
+
+    // Later actions win every conflict
+    rebind.resolve_conflicts_by(|a, b| b.cmp(a));
+
+    let pairs = rebind.button_action_pairs();
+    for &(button, _) in &pairs {
+        assert_eq!(pairs.iter().filter(|&&(b, _)| b == button).count(), 1);
+    }
+    assert_eq!(rebind.get_bindings(&TestAction::Action1),
+               Some(&ButtonTuple(Some(Keyboard(Key::Up)), None, None)));
+    assert_eq!(rebind.get_bindings(&TestAction::Action2),
+               Some(&ButtonTuple(Some(Keyboard(Key::Down)), None, None)));
+    assert_eq!(rebind.get_bindings(&TestAction::Action5),
+               Some(&ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::Space)), None)));
+    assert_eq!(rebind.get_bindings(&TestAction::Action6),
+               Some(&ButtonTuple(Some(Keyboard(Key::S)), None, None)));
+}
+
+#[test]
+fn test_rebind_change_listener() {
+    use std::sync::{Arc, Mutex};
+    let changes = Arc::new(Mutex::new(vec![]));
+    let mut rebind: TestRebind = create_prepopulated_builder().build_rebind();
+
+    let listener_changes = changes.clone();
+    rebind.set_change_listener(Box::new(move |&a, &bt| listener_changes.lock().unwrap().push((a, bt))));
+
+    let new_buttons = ButtonTuple(Some(Keyboard(Key::Space)), None, None);
+    rebind.insert_action_with_buttons(TestAction::Action1, new_buttons).unwrap();
+    assert_eq!(*changes.lock().unwrap(), vec![(TestAction::Action1, new_buttons)]);
+
+    rebind.remove_change_listener();
+    rebind.insert_action(TestAction::Action2);
+    assert_eq!(changes.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_translator_retain_bindings() {
+    use input::{Button, ControllerButton};
+    use rebind::Device;
+    let pad_a = Button::Controller(ControllerButton { id: 0, button: 0 });
+    let mut translator = create_prepopulated_builder()
+                             .with_mapping(TestAction::Action5, pad_a)
+                             .build_translator();
+
+    translator.retain_bindings(|b, _| Device::from_button(b) == Device::Keyboard);
+    assert_eq!(translator.translate(&Input::Press(pad_a)), None);
+    assert_eq!(translator.translate(&Input::Press(Keyboard(Key::W))),
+               Some(Translated::Press(TestAction::Action1)));
+}
+
+#[test]
+fn test_translator_active_bindings() {
+    let mut translator = create_prepopulated_builder().build_translator();
+    assert!(!translator.is_action_active(&TestAction::Action1));
+    assert_eq!(translator.active_bindings(&TestAction::Action1), vec![]);
+
+    translator.translate(&Input::Press(Keyboard(Key::W)));
+    translator.translate(&Input::Press(Keyboard(Key::S)));
+    assert!(translator.is_action_active(&TestAction::Action1));
+    assert_eq!(translator.active_bindings(&TestAction::Action1), vec![Keyboard(Key::W)]);
+
+    translator.translate(&Input::Release(Keyboard(Key::W)));
+    assert!(!translator.is_action_active(&TestAction::Action1));
+}
+
+#[test]
+fn test_static_translator() {
+    use input::{Button, Motion};
+    use rebind::StaticTranslator;
+    // Sorted by `OrderedButton`, i.e. by key code
+    static BINDINGS: [(Button, TestAction); 8] = [(Keyboard(Key::A), TestAction::Action3),
+                                                  (Keyboard(Key::D), TestAction::Action4),
+                                                  (Keyboard(Key::S), TestAction::Action2),
+                                                  (Keyboard(Key::W), TestAction::Action1),
+                                                  (Keyboard(Key::Right), TestAction::Action4),
+                                                  (Keyboard(Key::Left), TestAction::Action3),
+                                                  (Keyboard(Key::Down), TestAction::Action2),
+                                                  (Keyboard(Key::Up), TestAction::Action1)];
+    let static_translator = StaticTranslator::new(&BINDINGS);
+    let mut translator = create_prepopulated_builder().build_translator();
+
+    let inputs = [Input::Press(Keyboard(Key::W)),
+                  Input::Press(Keyboard(Key::Left)),
+                  Input::Press(Keyboard(Key::Z)),
+                  Input::Release(Keyboard(Key::Left)),
+                  Input::Release(Keyboard(Key::W)),
+                  Input::Move(Motion::MouseCursor(10.0, 20.0)),
+                  Input::Move(Motion::MouseScroll(0.0, 1.0))];
+    for input in &inputs {
+        assert_eq!(static_translator.translate(input), translator.translate(input));
+    }
+}
+
+#[test]
+fn test_button_tuple_enumerated() {
+    let bt = ButtonTuple(None, Some(Keyboard(Key::B)), Some(Keyboard(Key::C)));
+    assert_eq!(bt.enumerated(),
+               [(0, None), (1, Some(Keyboard(Key::B))), (2, Some(Keyboard(Key::C)))]);
+}
+
+#[test]
+fn test_translator_scroll_step() {
+    let mut translator = create_prepopulated_builder().build_translator();
+    assert_eq!(translator.scroll_step(0.25), 0);
+    assert_eq!(translator.scroll_step(0.5), 0);
+    assert_eq!(translator.scroll_step(0.5), 1);
+    assert_eq!(translator.scroll_step(2.0), 2);
+    assert_eq!(translator.scroll_step(-0.5), 0);
+    assert_eq!(translator.scroll_step(-1.0), -1);
+}
+
+#[test]
+fn test_rebind_is_button_bound() {
+    let rebind: TestRebind = create_prepopulated_builder().build_rebind();
+    assert!(rebind.is_button_bound(&Keyboard(Key::W)));
+    assert!(!rebind.is_button_bound(&Keyboard(Key::Q)));
+}
+
+#[test]
+fn test_dpi_scale() {
+    use input::Motion;
+    let mut translator = create_prepopulated_builder().dpi_scale(2.0).build_translator();
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseCursor(100.0, 50.0))),
+               Some(Translated::Move(Motion::MouseCursor(50.0, 25.0))));
+
+    let mut rebind = translator.into_rebind();
+    assert_eq!(rebind.get_dpi_scale(), 2.0);
+    rebind.set_x_motion_inverted(true);
+    let mut translator = rebind.into_translator();
+    // 800 - 100 = 700 physical pixels
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseCursor(100.0, 50.0))),
+               Some(Translated::Move(Motion::MouseCursor(350.0, 25.0))));
+}
+
+#[test]
+fn test_rebind_from_json_lenient() {
+    use rustc_serialize::json;
+    let rebind: TestRebind = create_prepopulated_builder().y_scroll_inverted(true).build_rebind();
+    let encoded = json::encode(&rebind).unwrap();
+
+    let (decoded, skipped): (TestRebind, _) = InputRebind::from_json_lenient(&encoded).unwrap();
+    assert_eq!(json::encode(&decoded).unwrap(), encoded);
+    assert!(skipped.is_empty());
+
+    // Simulate bindings saved by a newer version with an extra action
+    let newer = encoded.replace("\"Action4\"", "\"Action11\"");
+    let (decoded, skipped): (TestRebind, _) = InputRebind::from_json_lenient(&newer).unwrap();
+    assert_eq!(skipped, vec!["Action11".to_string()]);
+    assert_eq!(decoded.actions(),
+               vec![TestAction::Action1, TestAction::Action2, TestAction::Action3]);
+    assert_eq!(decoded.get_bindings(&TestAction::Action1), rebind.get_bindings(&TestAction::Action1));
+    assert!(decoded.get_y_scroll_inverted());
+
+    assert!(TestRebind::from_json_lenient("[1, 2]").is_err());
+}
+
+#[test]
+fn test_rebind_actions_by_category() {
+    let mut rebind: TestRebind = create_prepopulated_builder().with_action(TestAction::Action5).build_rebind();
+    rebind.set_category(TestAction::Action4, "Movement");
+    rebind.set_category(TestAction::Action1, "Movement");
+    rebind.set_category(TestAction::Action5, "Combat");
+    assert_eq!(rebind.get_category(&TestAction::Action1), Some("Movement"));
+    assert_eq!(rebind.get_category(&TestAction::Action2), None);
+
+    let groups = rebind.actions_by_category();
+    assert_eq!(groups.keys().collect::<Vec<_>>(), vec!["", "Combat", "Movement"]);
+    assert_eq!(groups[""], vec![TestAction::Action2, TestAction::Action3]);
+    assert_eq!(groups["Combat"], vec![TestAction::Action5]);
+    assert_eq!(groups["Movement"], vec![TestAction::Action1, TestAction::Action4]);
+}
+
+#[test]
+fn test_normalized_cursor_inversion() {
+    use input::Motion;
+    let cursor = |x, y| Input::Move(Motion::MouseCursor(x, y));
+    let normalized = |x, y| Some(Translated::Move(Motion::MouseCursor(x, y)));
+
+    let mut translator: TestTranslator = Builder::new((1600, 900)).normalize_cursor(true).build_translator();
+    assert_eq!(translator.translate(&cursor(0.0, 0.0)), normalized(0.0, 0.0));
+    assert_eq!(translator.translate(&cursor(1600.0, 900.0)), normalized(1.0, 1.0));
+    assert_eq!(translator.translate(&cursor(800.0, 450.0)), normalized(0.5, 0.5));
+    assert_eq!(translator.translate(&cursor(400.0, 675.0)), normalized(0.25, 0.75));
+
+    let mut translator: TestTranslator = Builder::new((1600, 900))
+                                             .normalize_cursor(true)
+                                             .x_motion_inverted(true)
+                                             .y_motion_inverted(true)
+                                             .build_translator();
+    assert_eq!(translator.translate(&cursor(0.0, 0.0)), normalized(1.0, 1.0));
+    assert_eq!(translator.translate(&cursor(1600.0, 0.0)), normalized(0.0, 1.0));
+    assert_eq!(translator.translate(&cursor(0.0, 900.0)), normalized(1.0, 0.0));
+    assert_eq!(translator.translate(&cursor(800.0, 450.0)), normalized(0.5, 0.5));
+    assert_eq!(translator.translate(&cursor(400.0, 675.0)), normalized(0.75, 0.25));
+}
+
+#[test]
+fn test_translator_distinct_action_count() {
+    let translator: TestTranslator = Builder::new(TEST_SIZE)
+                                         .with_mapping(TestAction::Action1, Keyboard(Key::W))
+                                         .with_mapping(TestAction::Action1, Keyboard(Key::Up))
+                                         .with_mapping(TestAction::Action2, Keyboard(Key::S))
+                                         .with_mapping(TestAction::Action2, Keyboard(Key::Down))
+                                         .with_mapping(TestAction::Action2, Keyboard(Key::J))
+                                         .build_translator();
+    assert_eq!(translator.distinct_action_count(), 2);
+}
+
+#[test]
+fn test_rebind_journal() {
+    use rebind::RebindJournal;
+    let mut journal = RebindJournal::new(create_prepopulated_builder().build_rebind() as TestRebind);
+    let space = ButtonTuple(Some(Keyboard(Key::Space)), None, None);
+
+    journal.insert_action_with_buttons(TestAction::Action1, space).unwrap();
+    journal.add_button(TestAction::Action1, Keyboard(Key::J)).unwrap();
+    assert!(journal.add_button(TestAction::Action1, Keyboard(Key::J)).is_ok());
+    journal.insert_action(TestAction::Action2);
+
+    let entries = journal.entries();
+    assert_eq!(entries.iter().map(|e| (e.action, e.buttons)).collect::<Vec<_>>(),
+               vec![(TestAction::Action1, Some(space)),
+                    (TestAction::Action1, Some(ButtonTuple(Some(Keyboard(Key::Space)), Some(Keyboard(Key::J)), None))),
+                    (TestAction::Action2, Some(ButtonTuple::new()))]);
+    assert!(entries.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+
+    assert!(!journal.try_insert_action(TestAction::Action2));
+    assert!(journal.try_insert_action(TestAction::Action9));
+    let entries = journal.entries();
+    assert_eq!(entries.len(), 4);
+    assert_eq!((entries[3].action, entries[3].buttons), (TestAction::Action9, Some(ButtonTuple::new())));
+
+    let last = entries[3].timestamp;
+    assert_eq!(journal.since(last).last(), entries.last());
+    assert!(journal.since(last + 1).is_empty());
+    assert_eq!(journal.into_rebind().get_bindings(&TestAction::Action2), Some(&ButtonTuple::new()));
+}
+
+#[test]
+fn test_rebind_journal_records_bulk_changes() {
+    use rebind::RebindJournal;
+    use std::collections::HashMap;
+    let mut journal = RebindJournal::new(create_prepopulated_builder().build_rebind() as TestRebind);
+
+    journal.add_buttons(TestAction::Action1, ButtonTuple(Some(Keyboard(Key::J)), None, None)).unwrap();
+    assert!(journal.rename_action(&TestAction::Action1, TestAction::Action5));
+    assert!(!journal.rename_action(&TestAction::Action1, TestAction::Action6));
+    let merged = ButtonTuple(Some(Keyboard(Key::Up)), Some(Keyboard(Key::W)), Some(Keyboard(Key::J)));
+    assert_eq!(journal.entries().iter().map(|e| (e.action, e.buttons)).collect::<Vec<_>>(),
+               vec![(TestAction::Action1, Some(merged)),
+                    (TestAction::Action1, None),
+                    (TestAction::Action5, Some(merged))]);
+
+    let shared = ButtonTuple(Some(Keyboard(Key::Q)), None, None);
+    let mut bindings = HashMap::new();
+    bindings.insert(TestAction::Action2, shared);
+    bindings.insert(TestAction::Action3, shared);
+    journal.set_bindings(bindings).unwrap();
+    assert_eq!(journal.entries()[3..].iter().map(|e| (e.action, e.buttons)).collect::<Vec<_>>(),
+               vec![(TestAction::Action2, Some(shared)),
+                    (TestAction::Action3, Some(shared)),
+                    (TestAction::Action4, None),
+                    (TestAction::Action5, None)]);
+
+    journal.resolve_conflicts_by(|a, b| a.cmp(b));
+    assert_eq!(journal.entries()[7..].iter().map(|e| (e.action, e.buttons)).collect::<Vec<_>>(),
+               vec![(TestAction::Action3, Some(ButtonTuple::new()))]);
+}
+
+#[test]
+fn test_translator_with_size() {
+    let translator: TestTranslator = InputTranslator::new((800, 600)).with_size((1024, 768));
+    assert_eq!(translator.into_rebind().get_viewport_size(), Size { width: 1024, height: 768 });
+}
+
+#[test]
+fn test_rebind_preview_assign() {
+    let mut rebind: TestRebind = create_prepopulated_builder()
+                                     .with_mapping(TestAction::Action5, Keyboard(Key::W))
+                                     .build_rebind();
+    let proposed = ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::Q)), Some(Keyboard(Key::Up)));
+
+    let preview = rebind.preview_assign(&TestAction::Action1, &proposed);
+    assert!(!preview.is_clear());
+    assert_eq!(preview.conflicts,
+               vec![(Keyboard(Key::W), vec![TestAction::Action5])]);
+    assert!(preview.over_limit.is_empty());
+
+    rebind.set_max_actions_per_button(Some(1));
+    let preview = rebind.preview_assign(&TestAction::Action2, &proposed);
+    assert_eq!(preview.conflicts,
+               vec![(Keyboard(Key::W), vec![TestAction::Action1, TestAction::Action5]),
+                    (Keyboard(Key::Up), vec![TestAction::Action1])]);
+    assert_eq!(preview.over_limit, vec![Keyboard(Key::W), Keyboard(Key::Up)]);
+
+    // The preview doesn't change the bindings
+    assert_eq!(rebind.get_bindings(&TestAction::Action2),
+               Some(&ButtonTuple(Some(Keyboard(Key::Down)), Some(Keyboard(Key::S)), None)));
+    assert!(rebind.preview_assign(&TestAction::Action2, &ButtonTuple(Some(Keyboard(Key::Q)), None, None)).is_clear());
+}
+
+#[test]
+fn test_rebind_preview_assign_reports_overflow() {
+    let mut rebind: TestRebind = create_prepopulated_builder()
+                                     .with_mapping(TestAction::Action1, Keyboard(Key::I))
+                                     .build_rebind();
+    rebind.set_max_bindings(Some(9));
+    // Action1 is bound to Up, W and I, but the proposed buttons replace them rather than
+    // being added to them
+    let proposed = ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::Q)), Some(Keyboard(Key::E)));
+
+    let preview = rebind.preview_assign(&TestAction::Action1, &proposed);
+    assert_eq!(preview.overflow, 0);
+    assert!(preview.is_clear());
+    assert_eq!(rebind.add_buttons(TestAction::Action1, proposed), Err(2));
+    assert!(rebind.insert_action_with_buttons(TestAction::Action1, proposed).is_ok());
+
+    let extra = ButtonTuple(Some(Keyboard(Key::K)), Some(Keyboard(Key::L)), None);
+    let preview = rebind.preview_assign(&TestAction::Action5, &extra);
+    assert_eq!(preview.overflow, 2);
+    assert!(preview.conflicts.is_empty());
+    assert!(!preview.is_clear());
+    assert_eq!(rebind.insert_action_with_buttons(TestAction::Action5, extra), Err(RebindError::TooManyBindings));
+}
+
+#[test]
+fn test_translator_reconcile() {
+    let mut translator = create_prepopulated_builder().build_translator();
+    let mut rebind = translator.clone().into_rebind();
+
+    translator.translate(&Input::Press(Keyboard(Key::W)));
+    translator.translate(&Input::Press(Keyboard(Key::S)));
+    assert_eq!(translator.reconcile(), vec![]);
+
+    rebind.insert_action_with_buttons(TestAction::Action1, ButtonTuple(Some(Keyboard(Key::Up)), None, None)).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action5, ButtonTuple(Some(Keyboard(Key::W)), None, None)).unwrap();
+    rebind.update_translator(&mut translator);
+
+    assert_eq!(translator.reconcile(), vec![Translated::Release(TestAction::Action1)]);
+    assert_eq!(translator.reconcile(), vec![]);
+    assert_eq!(translator.translate(&Input::Release(Keyboard(Key::S))),
+               Some(Translated::Release(TestAction::Action2)));
+}
+
+#[test]
+fn test_same_bindings_as() {
+    let translator = create_prepopulated_builder().x_motion_inverted(true).build_translator();
+    let mut rebind = translator.clone().into_rebind();
+    rebind.set_x_motion_inverted(false);
+    assert!(translator.same_bindings_as(&rebind));
+    assert!(rebind.same_bindings_as(&translator));
+
+    rebind.insert_action(TestAction::Action5);
+    assert!(translator.same_bindings_as(&rebind));
+
+    rebind.insert_action_with_buttons(TestAction::Action5, ButtonTuple(Some(Keyboard(Key::Q)), None, None)).unwrap();
+    assert!(!translator.same_bindings_as(&rebind));
+    assert!(!rebind.same_bindings_as(&translator));
+}
+
+#[test]
+fn test_reset_mouse() {
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    let bindings = rebind.clone_bindings_only();
+    rebind.set_viewport_size((1024, 768));
+    rebind.set_x_motion_inverted(true);
+    rebind.set_y_scroll_inverted(true);
+    rebind.set_mouse_sensitivity(2.5);
+
+    rebind.reset_mouse();
+    assert!(!rebind.get_x_motion_inverted());
+    assert!(!rebind.get_y_scroll_inverted());
+    assert_eq!(rebind.get_mouse_sensitivity(), 1.0);
+    assert_eq!(rebind.get_viewport_size(), Size { width: 1024, height: 768 });
+    for action in bindings.actions() {
+        assert_eq!(rebind.get_bindings(&action), bindings.get_bindings(&action));
+    }
+    assert_eq!(rebind.actions(), bindings.actions());
+}
+
+#[test]
+fn test_add_buttons() {
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    let extra = ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::I)), None);
+    assert_eq!(rebind.add_buttons(TestAction::Action1, extra), Ok(()));
+    assert_eq!(rebind.get_bindings(&TestAction::Action1),
+               Some(&ButtonTuple(Some(Keyboard(Key::Up)), Some(Keyboard(Key::W)), Some(Keyboard(Key::I)))));
+
+    let overflow = ButtonTuple(Some(Keyboard(Key::J)), Some(Keyboard(Key::K)), Some(Keyboard(Key::Down)));
+    assert_eq!(rebind.add_buttons(TestAction::Action2, overflow), Err(1));
+    let three = ButtonTuple(Some(Keyboard(Key::J)), Some(Keyboard(Key::K)), Some(Keyboard(Key::L)));
+    assert_eq!(rebind.add_buttons(TestAction::Action2, three), Err(2));
+    assert_eq!(rebind.get_bindings(&TestAction::Action2),
+               Some(&ButtonTuple(Some(Keyboard(Key::Down)), Some(Keyboard(Key::S)), None)));
+
+    assert_eq!(rebind.add_buttons(TestAction::Action5, overflow), Ok(()));
+    assert_eq!(rebind.get_bindings(&TestAction::Action5), Some(&overflow));
+}
+
+#[test]
+fn test_add_buttons_respects_max_actions_per_button() {
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    rebind.set_max_actions_per_button(Some(1));
+
+    let buttons = ButtonTuple(Some(Keyboard(Key::I)), Some(Keyboard(Key::Up)), None);
+    assert_eq!(rebind.add_buttons(TestAction::Action5, buttons), Err(1));
+    assert_eq!(rebind.get_bindings(&TestAction::Action5), None);
+
+    // Buttons already bound to the action itself don't count against the limit
+    let buttons = ButtonTuple(Some(Keyboard(Key::Up)), Some(Keyboard(Key::I)), None);
+    assert_eq!(rebind.add_buttons(TestAction::Action1, buttons), Ok(()));
+}
+
+#[test]
+fn test_mouse_profile_store() {
+    use rebind::MouseProfileStore;
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    let mut store = MouseProfileStore::new();
+    rebind.set_y_motion_inverted(true);
+    rebind.set_mouse_sensitivity(1.5);
+    store.save(1, &rebind);
+
+    rebind.set_y_motion_inverted(false);
+    rebind.set_x_scroll_inverted(true);
+    rebind.set_mouse_sensitivity(3.0);
+    rebind.set_viewport_size((1024, 768));
+    assert!(store.load(1, &mut rebind));
+    assert!(rebind.get_y_motion_inverted());
+    assert!(!rebind.get_x_scroll_inverted());
+    assert_eq!(rebind.get_mouse_sensitivity(), 1.5);
+    assert_eq!(rebind.get_viewport_size(), Size { width: 1024, height: 768 });
+    assert!(!store.load(2, &mut rebind));
+}
+
+#[test]
+fn test_preview_motion() {
+    use input::Motion;
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    rebind.set_x_motion_inverted(true);
+    rebind.set_max_mouse_delta(Some(50.0));
+    rebind.set_dpi_scale(2.0);
+    let mut translator = rebind.clone().into_translator();
+
+    let motions = [Motion::MouseRelative(100.0, 0.0), Motion::MouseCursor(200.0, 100.0), Motion::MouseScroll(1.0, 2.0)];
+    for &motion in &motions {
+        assert_eq!(translator.translate(&Input::Move(motion)), Some(Translated::Move(rebind.preview_motion(motion))));
+    }
+    assert_eq!(rebind.preview_motion(Motion::MouseRelative(100.0, 0.0)), Motion::MouseRelative(-50.0, 0.0));
+}
+
+#[test]
+fn test_button_tuple_vec_conversion() {
+    use std::convert::TryFrom;
+    let buttons: Vec<input::Button> = ButtonTuple(None, Some(Keyboard(Key::A)), Some(Keyboard(Key::B))).into();
+    assert_eq!(buttons, vec![Keyboard(Key::A), Keyboard(Key::B)]);
+    assert_eq!(ButtonTuple::try_from(buttons),
+               Ok(ButtonTuple(Some(Keyboard(Key::A)), Some(Keyboard(Key::B)), None)));
+    assert_eq!(ButtonTuple::try_from(vec![]), Ok(ButtonTuple::new()));
+
+    let too_many = vec![Keyboard(Key::A), Keyboard(Key::B), Keyboard(Key::C), Keyboard(Key::D), Keyboard(Key::E)];
+    assert_eq!(ButtonTuple::try_from(too_many), Err(2));
+}
+
+#[test]
+fn test_conversion_dedups_buttons() {
+    let builder = TestBuilder::new(TEST_SIZE).with_mapping(TestAction::Action1, Keyboard(Key::W))
+                                             .with_mapping(TestAction::Action1, Keyboard(Key::W))
+                                             .with_mapping(TestAction::Action1, Keyboard(Key::Up));
+    let rebind = builder.build_rebind();
+    assert_eq!(rebind.get_bindings(&TestAction::Action1),
+               Some(&ButtonTuple(Some(Keyboard(Key::W)), Some(Keyboard(Key::Up)), None)));
+}
+
+#[test]
+fn test_lock_to_center() {
+    use input::Motion;
+    let builder = create_prepopulated_builder().lock_to_center(true);
+    assert!(builder.get_lock_to_center());
+    let mut translator = builder.build_translator();
+
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseCursor(400.0, 300.0))),
+               Some(Translated::Move(Motion::MouseRelative(0.0, 0.0))));
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseCursor(410.0, 280.0))),
+               Some(Translated::Move(Motion::MouseRelative(10.0, -20.0))));
+}
+
+#[test]
+fn test_transaction_rollback() {
+    use rustc_serialize::json;
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    let original = json::encode(&rebind).unwrap();
+    {
+        let mut transaction = rebind.begin_transaction();
+        transaction.insert_action_with_buttons(TestAction::Action1,
+                                               ButtonTuple(Some(Keyboard(Key::I)), None, None)).unwrap();
+        transaction.insert_action(TestAction::Action5);
+        transaction.set_x_motion_inverted(true);
+    }
+    assert_eq!(json::encode(&rebind).unwrap(), original);
+}
+
+#[test]
+fn test_transaction_commit() {
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    {
+        let mut transaction = rebind.begin_transaction();
+        transaction.insert_action_with_buttons(TestAction::Action1,
+                                               ButtonTuple(Some(Keyboard(Key::I)), None, None)).unwrap();
+        transaction.set_x_motion_inverted(true);
+        transaction.commit();
+    }
+    assert_eq!(rebind.get_bindings(&TestAction::Action1),
+               Some(&ButtonTuple(Some(Keyboard(Key::I)), None, None)));
+    assert!(rebind.get_x_motion_inverted());
+}
+
+#[test]
+fn test_candidate_actions() {
+    use rebind::ModifierMask;
+    let translator = create_prepopulated_builder()
+                         .with_modified_mapping(TestAction::Action5, Keyboard(Key::S), ModifierMask::CTRL)
+                         .with_modified_mapping(TestAction::Action6, Keyboard(Key::Z), ModifierMask::SHIFT)
+                         .build_translator();
+
+    assert_eq!(translator.candidate_actions(&[Keyboard(Key::LCtrl)]), vec![TestAction::Action5]);
+    assert_eq!(translator.candidate_actions(&[Keyboard(Key::RCtrl), Keyboard(Key::S)]),
+               vec![TestAction::Action5]);
+    assert_eq!(translator.candidate_actions(&[Keyboard(Key::Q)]), vec![]);
+    assert_eq!(translator.candidate_actions(&[Keyboard(Key::LCtrl), Keyboard(Key::LShift)]), vec![]);
+}
+
+#[test]
+fn test_translator_viewport_size() {
+    let mut translator = create_prepopulated_builder_with_size(Size { width: 1024, height: 768 }).build_translator();
+    assert_eq!(translator.viewport_size(), Size { width: 1024, height: 768 });
+    translator.set_size((640, 480));
+    assert_eq!(translator.viewport_size(), Size { width: 640, height: 480 });
+}
+
+#[test]
+fn test_rebind_from_json_with_aliases() {
+    use rebind::AliasTable;
+    use rustc_serialize::json;
+    let rebind: TestRebind = create_prepopulated_builder().build_rebind();
+    let legacy = json::encode(&rebind).unwrap().replace("\"Action4\"", "\"Leap\"");
+
+    let mut aliases = AliasTable::new();
+    aliases.add_alias("Leap", TestAction::Action4);
+    aliases.add_alias("Jump", TestAction::Action4);
+    let (decoded, skipped): (TestRebind, _) = InputRebind::from_json_with_aliases(&legacy, &aliases).unwrap();
+    assert!(skipped.is_empty());
+    assert_eq!(decoded.get_bindings(&TestAction::Action4), rebind.get_bindings(&TestAction::Action4));
+
+    let (_, skipped): (TestRebind, _) = InputRebind::from_json_lenient(&legacy).unwrap();
+    assert_eq!(skipped, vec!["Leap".to_string()]);
+}
+
+#[test]
+fn test_unreachable_actions() {
+    use input::{Button, ControllerButton};
+    use rebind::Device;
+    let pad = Button::Controller(ControllerButton { id: 0, button: 1 });
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    rebind.insert_action_with_buttons(TestAction::Action5, ButtonTuple(Some(pad), None, None)).unwrap();
+    rebind.insert_action_with_buttons(TestAction::Action6,
+                                      ButtonTuple(Some(pad), Some(Keyboard(Key::Q)), None)).unwrap();
+    rebind.insert_action(TestAction::Action7);
+
+    assert_eq!(rebind.unreachable_actions(&[Device::Keyboard, Device::Mouse]), vec![TestAction::Action5]);
+    assert_eq!(rebind.unreachable_actions(&[Device::Keyboard, Device::Controller(0)]), vec![]);
+    assert_eq!(rebind.unreachable_actions(&[Device::Controller(0)]).len(), 4);
+}
+
+#[test]
+fn test_translate_with_state() {
+    use std::collections::HashSet;
+    let translator = create_prepopulated_builder().build_translator();
+    let mut held = HashSet::new();
+
+    assert_eq!(translator.translate_with_state(&Input::Press(Keyboard(Key::W)), &mut held),
+               Some(Translated::Press(TestAction::Action1)));
+    assert_eq!(translator.translate_with_state(&Input::Press(Keyboard(Key::Up)), &mut held),
+               Some(Translated::Press(TestAction::Action1)));
+    assert_eq!(held.len(), 2);
+    assert_eq!(translator.translate_with_state(&Input::Release(Keyboard(Key::W)), &mut held), None);
+    assert_eq!(translator.translate_with_state(&Input::Release(Keyboard(Key::Up)), &mut held),
+               Some(Translated::Release(TestAction::Action1)));
+    assert!(held.is_empty());
+    assert!(!translator.is_action_active(&TestAction::Action1));
+}
+
+#[test]
+fn test_max_bindings() {
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    assert_eq!(rebind.binding_count(), 8);
+    rebind.set_max_bindings(Some(9));
+
+    assert_eq!(rebind.add_button(TestAction::Action1, Keyboard(Key::I)), Ok(()));
+    assert_eq!(rebind.add_button(TestAction::Action2, Keyboard(Key::K)), Err(RebindError::TooManyBindings));
+    assert_eq!(rebind.add_buttons(TestAction::Action5, ButtonTuple(Some(Keyboard(Key::K)), None, None)), Err(1));
+    assert_eq!(rebind.binding_count(), 9);
+
+    rebind.set_max_bindings(Some(11));
+    let buttons = ButtonTuple(Some(Keyboard(Key::K)), Some(Keyboard(Key::L)), None);
+    assert_eq!(rebind.add_buttons(TestAction::Action5, buttons), Ok(()));
+    assert_eq!(rebind.binding_count(), 11);
+
+    let more = ButtonTuple(Some(Keyboard(Key::K)), Some(Keyboard(Key::L)), Some(Keyboard(Key::Q)));
+    assert_eq!(rebind.insert_action_with_buttons(TestAction::Action5, more), Err(RebindError::TooManyBindings));
+    assert_eq!(rebind.insert_action_with_buttons(TestAction::Action5, ButtonTuple(Some(Keyboard(Key::Q)), None, None)),
+               Ok(Some(buttons)));
+    assert_eq!(rebind.binding_count(), 10);
+
+    let two = ButtonTuple(Some(Keyboard(Key::E)), Some(Keyboard(Key::R)), None);
+    assert_eq!(rebind.with_overrides(&[(TestAction::Action6, two)]).err(), Some(RebindError::TooManyBindings));
+    let mut rebind = rebind.with_overrides(&[(TestAction::Action6, ButtonTuple(Some(Keyboard(Key::E)), None, None))])
+                           .unwrap();
+    assert_eq!(rebind.binding_count(), 11);
+
+    let full = ButtonTuple(Some(Keyboard(Key::Z)), Some(Keyboard(Key::X)), Some(Keyboard(Key::C)));
+    let bindings = [TestAction::Action1, TestAction::Action2, TestAction::Action3, TestAction::Action4]
+        .iter()
+        .map(|&a| (a, full))
+        .collect::<std::collections::HashMap<_, _>>();
+    assert_eq!(rebind.set_bindings(bindings), Err(RebindError::TooManyBindings));
+    assert_eq!(rebind.binding_count(), 11);
+
+    assert!(rebind.rename_action(&TestAction::Action6, TestAction::Action7));
+    assert_eq!(rebind.binding_count(), 11);
+}
+
+#[test]
+fn test_rename_action() {
+    let mut rebind = create_prepopulated_builder().build_rebind();
+    let action1_buttons = *rebind.get_bindings(&TestAction::Action1).unwrap();
+
+    assert!(rebind.rename_action(&TestAction::Action1, TestAction::Action5));
+    assert_eq!(rebind.get_bindings(&TestAction::Action1), None);
+    assert_eq!(rebind.get_bindings(&TestAction::Action5), Some(&action1_buttons));
+    assert!(!rebind.rename_action(&TestAction::Action1, TestAction::Action6));
+
+    rebind.insert_action_with_buttons(TestAction::Action3, ButtonTuple(Some(Keyboard(Key::Left)), None, None)).unwrap();
+    assert!(rebind.rename_action(&TestAction::Action4, TestAction::Action3));
+    assert_eq!(rebind.get_bindings(&TestAction::Action3),
+               Some(&ButtonTuple(Some(Keyboard(Key::Left)), Some(Keyboard(Key::Right)), Some(Keyboard(Key::D)))));
+    assert_eq!(rebind.actions(), vec![TestAction::Action2, TestAction::Action3, TestAction::Action5]);
+}
+
+#[test]
+fn test_stable_action_records() {
+    use rebind::TranslatedRecord;
+
+    // The same actions, declared in a different order by a later version
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+    enum OldAction { Jump, Crouch }
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+    enum NewAction { Sprint, Crouch, Jump }
+    impl Action for OldAction { }
+    impl Action for NewAction { }
+
+    impl StableAction for OldAction {
+        fn stable_id(&self) -> u32 {
+            match *self { OldAction::Jump => 10, OldAction::Crouch => 20 }
+        }
+        fn from_stable_id(id: u32) -> Option<Self> {
+            match id { 10 => Some(OldAction::Jump), 20 => Some(OldAction::Crouch), _ => None }
+        }
+    }
+    impl StableAction for NewAction {
+        fn stable_id(&self) -> u32 {
+            match *self { NewAction::Jump => 10, NewAction::Crouch => 20, NewAction::Sprint => 30 }
+        }
+        fn from_stable_id(id: u32) -> Option<Self> {
+            match id {
+                10 => Some(NewAction::Jump),
+                20 => Some(NewAction::Crouch),
+                30 => Some(NewAction::Sprint),
+                _ => None,
+            }
+        }
+    }
+
+    let saved = [Translated::Press(OldAction::Jump), Translated::Release(OldAction::Crouch)]
+                    .iter()
+                    .map(Translated::to_stable_record)
+                    .collect::<Vec<_>>();
+    let loaded = saved.iter().map(|r| Translated::from_stable_record(r).unwrap()).collect::<Vec<_>>();
+    assert_eq!(loaded, vec![Translated::Press(NewAction::Jump), Translated::Release(NewAction::Crouch)]);
+    assert_eq!(Translated::<OldAction>::from_stable_record(&TranslatedRecord::Press(30)), None);
+}
+
+#[test]
+fn test_rebind_to_sorted_string() {
+    let mut rebind = create_prepopulated_builder().y_scroll_inverted(true).build_rebind();
+    let reordered = ButtonTuple(None, Some(Keyboard(Key::S)), Some(Keyboard(Key::Down)));
+    rebind.insert_action_with_buttons(TestAction::Action2, reordered).unwrap();
+    rebind.insert_action(TestAction::Action5);
+
+    let expected = "[bindings]\n\
+                    Action1 = kb:W, kb:Up\n\
+                    Action2 = kb:S, kb:Down\n\
+                    Action3 = kb:A, kb:Left\n\
+                    Action4 = kb:D, kb:Right\n\
+                    Action5 = \n\
+                    [mouse]\n\
+                    x_motion_inverted = false\n\
+                    y_motion_inverted = false\n\
+                    x_scroll_inverted = false\n\
+                    y_scroll_inverted = true\n\
+                    sensitivity = 1.0\n\
+                    max_delta = None\n\
+                    scroll_as_motion = false\n\
+                    scroll_motion_factor = 1.0\n\
+                    strict_motion = false\n\
+                    dpi_scale = 1.0\n\
+                    normalize_cursor = false\n\
+                    lock_to_center = false\n\
+                    viewport_size = 800x600\n";
+    assert_eq!(rebind.to_sorted_string(), expected);
+    assert_eq!(rebind.clone().to_sorted_string(), rebind.to_sorted_string());
+}
+
+#[test]
+fn test_translator_debounce() {
+    use std::time::Duration;
+    let ms = Duration::from_millis;
+    let mut translator = create_prepopulated_builder().build_translator();
+    translator.set_debounce(ms(10));
+
+    assert_eq!(translator.translate_at(&Input::Press(Keyboard(Key::W)), ms(0)),
+               vec![Translated::Press(TestAction::Action1)]);
+    assert_eq!(translator.translate_at(&Input::Release(Keyboard(Key::W)), ms(100)), vec![]);
+    assert_eq!(translator.translate_at(&Input::Press(Keyboard(Key::W)), ms(104)), vec![]);
+    assert!(translator.is_action_active(&TestAction::Action1));
+
+    assert_eq!(translator.translate_at(&Input::Release(Keyboard(Key::W)), ms(200)), vec![]);
+    assert_eq!(translator.translate_at(&Input::Press(Keyboard(Key::W)), ms(250)),
+               vec![Translated::Release(TestAction::Action1), Translated::Press(TestAction::Action1)]);
+    assert_eq!(translator.translate_at(&Input::Release(Keyboard(Key::W)), ms(300)), vec![]);
+    assert_eq!(translator.poll_at(ms(305)), vec![]);
+    assert_eq!(translator.poll_at(ms(311)), vec![Translated::Release(TestAction::Action1)]);
+    assert!(!translator.is_action_active(&TestAction::Action1));
+}
+
+#[test]
+fn test_tap_hold_mapping_tap() {
+    use std::time::Duration;
+    let ms = Duration::from_millis;
+    let mut translator = create_prepopulated_builder()
+                             .with_tap_hold_mapping(Keyboard(Key::R), TestAction::Action5, TestAction::Action6, ms(300))
+                             .build_translator();
+
+    assert_eq!(translator.translate_at(&Input::Press(Keyboard(Key::R)), ms(1000)), vec![]);
+    assert_eq!(translator.poll_at(ms(1100)), vec![]);
+    assert_eq!(translator.translate_at(&Input::Release(Keyboard(Key::R)), ms(1200)),
+               vec![Translated::Press(TestAction::Action5), Translated::Release(TestAction::Action5)]);
+    assert_eq!(translator.poll_at(ms(2000)), vec![]);
+}
+
+#[test]
+fn test_tap_hold_mapping_serialization() {
+    use rustc_serialize::json;
+    use std::time::Duration;
+    let ms = Duration::from_millis;
+    let builder = create_prepopulated_builder()
+                      .with_tap_hold_mapping(Keyboard(Key::R), TestAction::Action5, TestAction::Action6, ms(300));
+    let decoded: TestBuilder = json::decode(&json::encode(&builder).unwrap()).unwrap();
+    let mut translator: TestTranslator = json::decode(&json::encode(&decoded.build_translator()).unwrap()).unwrap();
+
+    assert_eq!(translator.translate_at(&Input::Press(Keyboard(Key::R)), ms(1000)), vec![]);
+    assert_eq!(translator.poll_at(ms(1300)), vec![Translated::Press(TestAction::Action6)]);
+}
+
+#[test]
+fn test_tap_hold_mapping_hold() {
+    use std::time::Duration;
+    let ms = Duration::from_millis;
+    let mut translator = create_prepopulated_builder()
+                             .with_tap_hold_mapping(Keyboard(Key::R), TestAction::Action5, TestAction::Action6, ms(300))
+                             .build_translator();
+
+    assert_eq!(translator.translate_at(&Input::Press(Keyboard(Key::R)), ms(1000)), vec![]);
+    assert_eq!(translator.poll_at(ms(1300)), vec![Translated::Press(TestAction::Action6)]);
+    assert_eq!(translator.poll_at(ms(1400)), vec![]);
+    assert_eq!(translator.translate_at(&Input::Release(Keyboard(Key::R)), ms(2000)),
+               vec![Translated::Release(TestAction::Action6)]);
+}
+
+#[test]
+fn test_rebind_shrink_to_fit() {
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+    struct Id(u32);
+    impl Action for Id { }
+
+    let mut rebind: InputRebind<Id> = InputRebind::new(TEST_SIZE);
+    for i in 0..1000 {
+        rebind.insert_action(Id(i));
+    }
+    let peak = rebind.capacity();
+    assert!(peak >= 1000);
+
+    // Merging an action into another removes it
+    for i in 10..1000 {
+        rebind.rename_action(&Id(i), Id(0));
+    }
+    rebind.shrink_to_fit();
+    assert!(rebind.capacity() >= rebind.actions().len());
+    assert!(rebind.capacity() < peak);
+}
+
+#[test]
+fn test_translate_edge() {
+    use input::Motion;
+    let mut translator = create_prepopulated_builder().build_translator();
+    assert_eq!(translator.translate_edge(&Input::Press(Keyboard(Key::W))),
+               Some((Translated::Press(TestAction::Action1), true)));
+    assert_eq!(translator.translate_edge(&Input::Press(Keyboard(Key::W))),
+               Some((Translated::Press(TestAction::Action1), false)));
+    assert_eq!(translator.translate_edge(&Input::Release(Keyboard(Key::W))),
+               Some((Translated::Release(TestAction::Action1), true)));
+    assert_eq!(translator.translate_edge(&Input::Release(Keyboard(Key::W))),
+               Some((Translated::Release(TestAction::Action1), false)));
+    assert_eq!(translator.translate_edge(&Input::Move(Motion::MouseScroll(0.0, 1.0))),
+               Some((Translated::Move(Motion::MouseScroll(0.0, 1.0)), false)));
+}
+
+#[test]
+fn test_scroll_mapping_invert() {
+    use input::Motion;
+    use rebind::ScrollDir;
+    let scroll_up = Input::Move(Motion::MouseScroll(0.0, 1.0));
+    let scroll_down = Input::Move(Motion::MouseScroll(0.0, -1.0));
+
+    let mut translator = create_prepopulated_builder()
+                             .with_scroll_mapping(ScrollDir::Up, TestAction::Action5, false)
+                             .with_scroll_mapping(ScrollDir::Down, TestAction::Action6, false)
+                             .build_translator();
+    assert_eq!(translator.translate(&scroll_up), Some(Translated::Press(TestAction::Action5)));
+    assert_eq!(translator.translate(&scroll_down), Some(Translated::Press(TestAction::Action6)));
+
+    let mut translator = create_prepopulated_builder()
+                             .y_scroll_inverted(true)
+                             .with_scroll_mapping(ScrollDir::Up, TestAction::Action5, true)
+                             .build_translator();
+    assert_eq!(translator.translate(&scroll_down), Some(Translated::Press(TestAction::Action5)));
+    assert_eq!(translator.translate(&scroll_up),
+               Some(Translated::Move(Motion::MouseScroll(0.0, -1.0))));
+}
+
+#[test]
+fn test_scroll_mapping_serialization() {
+    use input::Motion;
+    use rebind::ScrollDir;
+    use rustc_serialize::json;
+    let builder = create_prepopulated_builder().with_scroll_mapping(ScrollDir::Up, TestAction::Action5, true);
+    let decoded: TestBuilder = json::decode(&json::encode(&builder).unwrap()).unwrap();
+    let mut translator: TestTranslator = json::decode(&json::encode(&decoded.build_translator()).unwrap()).unwrap();
+
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseScroll(0.0, -1.0))),
+               Some(Translated::Press(TestAction::Action5)));
+    assert_eq!(translator.translate(&Input::Move(Motion::MouseScroll(0.0, 1.0))),
+               Some(Translated::Move(Motion::MouseScroll(0.0, 1.0))));
+}
+
+#[cfg(feature = "proptest")]
+mod proptests {
+    use proptest::prelude::*;
+    use rebind::strategy;
+    use super::TestAction;
+
+    fn test_action() -> BoxedStrategy<TestAction> {
+        prop_oneof![Just(TestAction::Action1),
+                    Just(TestAction::Action2),
+                    Just(TestAction::Action3),
+                    Just(TestAction::Action4)]
+            .boxed()
+    }
+
+    proptest! {
+        #[test]
+        fn test_rebind_translator_round_trip(rebind in strategy::conflict_free_input_rebind(test_action())) {
+            let translator = rebind.clone().into_translator();
+            prop_assert!(translator.same_bindings_as(&rebind));
+            prop_assert!(translator.into_rebind().same_bindings_as(&rebind.into_translator()));
+        }
+    }
+}